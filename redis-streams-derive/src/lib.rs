@@ -0,0 +1,210 @@
+//! Derive macros for [`redis-streams`](https://github.com/grippy/redis-streams-rs).
+//!
+//! Enable the `derive` feature on `redis-streams` instead of depending
+//! on this crate directly.
+//!
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Lit, Meta, NestedMeta, Result};
+
+/// Derive `redis_streams::StreamMessage` for a struct with named fields.
+///
+/// See the `StreamMessage` trait docs for the supported `#[stream(..)]` attributes.
+///
+#[proc_macro_derive(StreamMessage, attributes(stream))]
+pub fn derive_stream_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    skip: bool,
+    default: bool,
+    json: bool,
+}
+
+fn parse_attrs(field: &syn::Field) -> Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+    for attr in field.attrs.iter().filter(|a| a.path.is_ident("stream")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected #[stream(...)]")),
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("rename") => {
+                    match nv.lit {
+                        Lit::Str(ref s) => attrs.rename = Some(s.value()),
+                        ref lit => {
+                            return Err(Error::new_spanned(lit, "expected a string literal"))
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("skip") => {
+                    attrs.skip = true
+                }
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("default") => {
+                    attrs.default = true
+                }
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("json") => {
+                    attrs.json = true
+                }
+                other => {
+                    return Err(Error::new_spanned(
+                        other,
+                        "unknown stream attribute, expected one of `rename`, `skip`, `default` or `json`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(attrs)
+}
+
+/// The `T` of a field written as `Option<T>`, with or without its path.
+/// Only these fields decode as `None` when missing from the message.
+fn option_arg(ty: &syn::Type) -> Option<&syn::Type> {
+    match ty {
+        syn::Type::Path(ref path) if path.qself.is_none() => {
+            let segment = path.path.segments.last()?;
+            if segment.ident != "Option" {
+                return None;
+            }
+            match segment.arguments {
+                syn::PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => {
+                    match args.args[0] {
+                        syn::GenericArgument::Type(ref ty) => Some(ty),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        syn::Type::Group(ref group) => option_arg(&group.elem),
+        syn::Type::Paren(ref paren) => option_arg(&paren.elem),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is written as `bool`. Redis writes a `bool` argument
+/// as `true`/`false` but can't read one back from a bulk string, so
+/// these fields are stored as `1`/`0` through `u8`.
+fn is_bool(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(ref path) if path.qself.is_none() => path.path.is_ident("bool"),
+        syn::Type::Group(ref group) => is_bool(&group.elem),
+        syn::Type::Paren(ref paren) => is_bool(&paren.elem),
+        _ => false,
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "StreamMessage can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "StreamMessage can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut encode = vec![];
+    let mut decode = vec![];
+
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let attrs = parse_attrs(field)?;
+
+        if attrs.skip {
+            decode.push(quote! {
+                #ident: ::std::default::Default::default()
+            });
+            continue;
+        }
+
+        let name = attrs
+            .rename
+            .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+
+        let inner = option_arg(&field.ty);
+        let optional = inner.is_some();
+        let (encode_fn, decode_fn) = match (attrs.json, attrs.default, optional) {
+            (false, true, _) => (quote!(encode_field), quote!(decode_field_or_default)),
+            (false, false, true) => (quote!(encode_field), quote!(decode_optional_field)),
+            (false, false, false) => (quote!(encode_field), quote!(decode_field)),
+            (true, true, _) => (
+                quote!(encode_json_field),
+                quote!(decode_json_field_or_default),
+            ),
+            (true, false, true) => (
+                quote!(encode_json_field),
+                quote!(decode_json_optional_field),
+            ),
+            (true, false, false) => (quote!(encode_json_field), quote!(decode_json_field)),
+        };
+
+        let (value, convert) = match inner {
+            _ if attrs.json => (quote!(self.#ident), None),
+            Some(ty) if is_bool(ty) => (
+                quote!(self.#ident.map(u8::from)),
+                Some(quote!(|v: ::std::option::Option<u8>| v.map(|v| v != 0))),
+            ),
+            None if is_bool(&field.ty) => {
+                (quote!(u8::from(self.#ident)), Some(quote!(|v: u8| v != 0)))
+            }
+            _ => (quote!(self.#ident), None),
+        };
+
+        let read = quote!(::redis_streams::__private::#decode_fn(stream_id, #name)?);
+        let read = match convert {
+            Some(convert) => quote!((#convert)(#read)),
+            None => read,
+        };
+
+        encode.push(quote! {
+            ::redis_streams::__private::#encode_fn(&mut fields, #name, &#value)?;
+        });
+        decode.push(quote! {
+            #ident: #read
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::redis_streams::StreamMessage for #ident #ty_generics #where_clause {
+            fn to_stream_fields(
+                &self,
+            ) -> ::redis_streams::RedisResult<::std::vec::Vec<(::std::string::String, ::std::vec::Vec<u8>)>> {
+                let mut fields = ::std::vec::Vec::new();
+                #(#encode)*
+                Ok(fields)
+            }
+
+            fn from_stream_id(
+                stream_id: &::redis_streams::StreamId,
+            ) -> ::redis_streams::RedisResult<Self> {
+                Ok(#ident {
+                    #(#decode),*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,194 @@
+use crate::commands::StreamPipelineCommands;
+use crate::cursor::StreamCursor;
+use crate::types::{StreamReadOptions, StreamReadReply};
+
+use redis::{ConnectionLike, RedisResult, ToRedisArgs};
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Stores the last processed `id` of each stream key in a Redis hash.
+///
+/// Give each reader its own `hash_key`. The hash has the same layout as
+/// `StreamCursor::save`, so `load` returns a cursor to resume from.
+///
+#[derive(Debug, Clone)]
+pub struct StreamCheckpointStore {
+    hash_key: String,
+}
+
+impl StreamCheckpointStore {
+    pub fn new(hash_key: &str) -> Self {
+        StreamCheckpointStore {
+            hash_key: hash_key.to_string(),
+        }
+    }
+
+    pub fn hash_key(&self) -> &str {
+        &self.hash_key
+    }
+
+    /// Load every stored key/id pair. A missing hash loads as an empty cursor.
+    pub fn load<C: ConnectionLike>(&self, con: &mut C) -> RedisResult<StreamCursor> {
+        StreamCursor::load(con, &self.hash_key)
+    }
+
+    /// Store the `ids` of the given keys, leaving other keys as they are.
+    pub fn save<C: ConnectionLike>(&self, con: &mut C, ids: &[(&str, &str)]) -> RedisResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        redis::cmd("HSET").arg(&self.hash_key).arg(ids).query(con)
+    }
+
+    /// Store the `ids` and add a message to `output_key` in one
+    /// `MULTI`/`EXEC` block, so a restarted reader never produces a
+    /// message twice. Returns the new message `id`.
+    pub fn save_with_xadd<C: ConnectionLike, F: ToRedisArgs, V: ToRedisArgs>(
+        &self,
+        con: &mut C,
+        ids: &[(&str, &str)],
+        output_key: &str,
+        items: &[(F, V)],
+    ) -> RedisResult<String> {
+        let mut pipe = redis::pipe();
+        pipe.atomic().xadd(output_key, "*", items);
+        if !ids.is_empty() {
+            pipe.hset_multiple(&self.hash_key, ids).ignore();
+        }
+        let (id,): (String,) = pipe.query(con)?;
+        Ok(id)
+    }
+}
+
+/// A non-group reader which resumes from a `StreamCheckpointStore`.
+///
+/// `start` loads the checkpoint and reads each key from its stored id, or
+/// from `start_id` for new keys. Mark messages with `processed` once
+/// they're handled. The marked ids are saved by the first `read` after
+/// `interval` has passed, by `save`, or atomically with an output message
+/// by `processed_with_xadd`. Messages read but not saved yet are read
+/// again after a restart.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamCheckpointReader,StreamCheckpointStore,StreamReadOptions};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut con = client.get_connection().unwrap();
+///
+/// let store = StreamCheckpointStore::new("checkpoints:reader-1");
+/// let mut reader = StreamCheckpointReader::start(&mut con, store, &["k1", "k2"], "0")
+///     .unwrap()
+///     .read_options(StreamReadOptions::default().block(1000).count(100));
+///
+/// loop {
+///     let reply = reader.read(&mut con).unwrap();
+///     for key in reply.keys {
+///         for msg in key.ids {
+///             // handle the message...
+///             reader.processed(&key.key, &msg.id.to_string());
+///         }
+///     }
+/// }
+/// ```
+///
+pub struct StreamCheckpointReader {
+    store: StreamCheckpointStore,
+    cursor: StreamCursor,
+    options: StreamReadOptions,
+    interval: Duration,
+    processed: BTreeMap<String, String>,
+    saved_at: Instant,
+}
+
+impl StreamCheckpointReader {
+    /// Load the checkpoint for `keys`. Keys without one start after `start_id`.
+    pub fn start<C: ConnectionLike>(
+        con: &mut C,
+        store: StreamCheckpointStore,
+        keys: &[&str],
+        start_id: &str,
+    ) -> RedisResult<Self> {
+        let saved = store.load(con)?;
+        let mut cursor = StreamCursor::new();
+        for key in keys {
+            cursor.insert(key, saved.get(key).unwrap_or(start_id));
+        }
+        Ok(StreamCheckpointReader {
+            store,
+            cursor,
+            options: StreamReadOptions::default(),
+            interval: Duration::from_secs(1),
+            processed: BTreeMap::new(),
+            saved_at: Instant::now(),
+        })
+    }
+
+    /// Set the `BLOCK` and `COUNT` options used for each read.
+    /// Don't set a `group`, this reader tracks ids itself.
+    pub fn read_options(mut self, options: StreamReadOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set how often `read` saves the processed ids. Defaults to 1s.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// The read position of each key.
+    pub fn cursor(&self) -> &StreamCursor {
+        &self.cursor
+    }
+
+    /// Save the processed ids if `interval` has passed, then
+    /// read the next messages of every key.
+    pub fn read<C: ConnectionLike>(&mut self, con: &mut C) -> RedisResult<StreamReadReply> {
+        if self.saved_at.elapsed() >= self.interval {
+            self.save(con)?;
+        }
+        self.cursor.read(con, self.options.clone())
+    }
+
+    /// Mark `id` as the last processed message of `key`.
+    pub fn processed(&mut self, key: &str, id: &str) {
+        self.processed.insert(key.to_string(), id.to_string());
+    }
+
+    /// Mark `id` as processed and save all marked ids together with
+    /// a message added to `output_key`. Returns the new message `id`.
+    pub fn processed_with_xadd<C: ConnectionLike, F: ToRedisArgs, V: ToRedisArgs>(
+        &mut self,
+        con: &mut C,
+        key: &str,
+        id: &str,
+        output_key: &str,
+        items: &[(F, V)],
+    ) -> RedisResult<String> {
+        self.processed(key, id);
+        let output_id = self
+            .store
+            .save_with_xadd(con, &self.processed_ids(), output_key, items)?;
+        self.saved();
+        Ok(output_id)
+    }
+
+    /// Save the processed ids now.
+    pub fn save<C: ConnectionLike>(&mut self, con: &mut C) -> RedisResult<()> {
+        self.store.save(con, &self.processed_ids())?;
+        self.saved();
+        Ok(())
+    }
+
+    fn processed_ids(&self) -> Vec<(&str, &str)> {
+        self.processed
+            .iter()
+            .map(|(key, id)| (key.as_str(), id.as_str()))
+            .collect()
+    }
+
+    fn saved(&mut self) {
+        self.processed.clear();
+        self.saved_at = Instant::now();
+    }
+}
@@ -1,10 +1,25 @@
+use crate::error::ignore_busy_group;
+use crate::iter::{StreamAutoClaimIter, StreamRangeIter, StreamRangePageIter};
+use crate::message::StreamMessage;
+use crate::script::{add_ack_invocation, add_ack_script};
 use crate::types::{
-    StreamClaimOptions, StreamClaimReply, StreamInfoConsumersReply, StreamInfoGroupsReply,
-    StreamInfoStreamReply, StreamMaxlen, StreamPendingCountReply, StreamPendingReply,
-    StreamRangeReply, StreamReadOptions, StreamReadReply,
+    StreamAddAckOptions, StreamAddOptions, StreamAutoClaimOptions, StreamAutoClaimReply,
+    StreamClaimOptions, StreamClaimReply, StreamEntryId, StreamGroupCreateOptions,
+    StreamInfoConsumersReply, StreamInfoGroupsReply, StreamInfoStreamFullReply,
+    StreamInfoStreamReply, StreamMaxlen, StreamPendingCountReply, StreamPendingOptions,
+    StreamPendingReply, StreamReadOptions, StreamSetIdOptions, StreamTrimOptions,
 };
 
-use redis::{cmd, ConnectionLike, FromRedisValue, RedisResult, ToRedisArgs};
+#[cfg(feature = "serde")]
+use crate::serialize::to_stream_fields;
+
+use redis::aio::ConnectionLike as AsyncConnectionLike;
+use redis::{
+    cmd, Cmd, ConnectionLike, FromRedisValue, Pipeline, RedisFuture, RedisResult, ToRedisArgs,
+};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
 
 /// Implementation of all redis stream commands.
 ///
@@ -52,6 +67,45 @@ pub trait StreamCommands: ConnectionLike + Sized {
         cmd("XADD").arg(key).arg(id).arg(map).query(self)
     }
 
+    // XADD key <ID or *> [serde struct] ...
+
+    /// Serde variant for adding a stream message by `key`.
+    /// The `message` struct (or map) is flattened to field/value pairs.
+    /// Use `*` as the `id` for the current timestamp.
+    ///
+    #[cfg(feature = "serde")]
+    #[inline]
+    fn xadd_serialize<
+        K: ToRedisArgs,
+        ID: ToRedisArgs,
+        T: Serialize + ?Sized,
+        RV: FromRedisValue,
+    >(
+        &mut self,
+        key: K,
+        id: ID,
+        message: &T,
+    ) -> RedisResult<RV> {
+        let items = to_stream_fields(message)?;
+        cmd("XADD").arg(key).arg(id).arg(items).query(self)
+    }
+
+    // XADD key <ID or *> [StreamMessage] ...
+
+    /// `StreamMessage` variant for adding a stream message by `key`.
+    /// Use `*` as the `id` for the current timestamp.
+    ///
+    #[inline]
+    fn xadd_message<K: ToRedisArgs, ID: ToRedisArgs, M: StreamMessage, RV: FromRedisValue>(
+        &mut self,
+        key: K,
+        id: ID,
+        message: &M,
+    ) -> RedisResult<RV> {
+        let items = message.to_stream_fields()?;
+        cmd("XADD").arg(key).arg(id).arg(items).query(self)
+    }
+
     // XADD key [MAXLEN [~|=] <count>] <ID or *> [field value] [field value] ...
 
     /// Add a stream message while capping the stream at a maxlength.
@@ -98,6 +152,102 @@ pub trait StreamCommands: ConnectionLike + Sized {
             .query(self)
     }
 
+    // XADD key [NOMKSTREAM] [<MAXLEN | MINID> [= | ~] threshold [LIMIT count]]
+    //     <ID or *> [field value] [field value] ...
+
+    /// Add a stream message with the optional arguments set by `StreamAddOptions`:
+    /// trimming by MAXLEN or MINID, LIMIT, NOMKSTREAM and an explicit `id`.
+    ///
+    /// ```no_run
+    /// use redis_streams::{client_open,RedisResult,StreamCommands,StreamAddOptions,StreamEntryId,StreamTrimOptions};
+    /// use std::time::{Duration,SystemTime};
+    /// let client = client_open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// // Keep about one hour of messages, evicting at most 100 per call.
+    ///
+    /// let hour_ago = SystemTime::now() - Duration::from_secs(3600);
+    /// let opts = StreamAddOptions::default().trim(
+    ///     StreamTrimOptions::minid(StreamEntryId::from_system_time(hour_ago))
+    ///         .approx()
+    ///         .limit(100),
+    /// );
+    /// let id: RedisResult<String> = con.xadd_options("k1", &[("h", "w")], opts);
+    ///
+    /// // Only add the message if the stream already exists.
+    ///
+    /// let opts = StreamAddOptions::default().nomkstream();
+    /// let id: RedisResult<Option<String>> = con.xadd_options("k1", &[("h", "w")], opts);
+    /// ```
+    ///
+    #[inline]
+    fn xadd_options<K: ToRedisArgs, F: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(
+        &mut self,
+        key: K,
+        items: &[(F, V)],
+        options: StreamAddOptions,
+    ) -> RedisResult<RV> {
+        cmd("XADD").arg(key).arg(options).arg(items).query(self)
+    }
+
+    // XADD key [NOMKSTREAM] [<MAXLEN | MINID> [= | ~] threshold [LIMIT count]]
+    //     <ID or *> [rust BTreeMap] ...
+
+    /// BTreeMap variant of `xadd_options`.
+    ///
+    #[inline]
+    fn xadd_options_map<K: ToRedisArgs, BTM: ToRedisArgs, RV: FromRedisValue>(
+        &mut self,
+        key: K,
+        map: BTM,
+        options: StreamAddOptions,
+    ) -> RedisResult<RV> {
+        cmd("XADD").arg(key).arg(options).arg(map).query(self)
+    }
+
+    // XADD <dest> ... + XACK <key> <group> <id> [+ XDEL <key> <id>]
+
+    /// Atomically add a message to the `dest` stream and ack `id` from
+    /// the source stream `key` for a consumer `group`, optionally
+    /// deleting it from the source. This runs as a single Lua script, so
+    /// a crash can't leave the message added but unacked (or the reverse).
+    ///
+    /// Returns the new `dest` id, or `None` without adding anything if
+    /// `id` is no longer pending for the `group` (e.g. it was already acked).
+    ///
+    /// ```no_run
+    /// use redis_streams::{client_open,StreamCommands,StreamAddAckOptions,StreamAddOptions,StreamTrimOptions};
+    /// let client = client_open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// let opts = StreamAddAckOptions::default()
+    ///     .add_options(StreamAddOptions::default().trim(StreamTrimOptions::maxlen(1000).approx()))
+    ///     .with_delete();
+    /// let id = con
+    ///     .xadd_xack("out", &[("total", 42)], "in", "g1", "1000-0", opts)
+    ///     .unwrap();
+    /// ```
+    ///
+    #[inline]
+    fn xadd_xack<
+        DK: ToRedisArgs,
+        F: ToRedisArgs,
+        V: ToRedisArgs,
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        ID: ToRedisArgs,
+    >(
+        &mut self,
+        dest: DK,
+        items: &[(F, V)],
+        key: K,
+        group: G,
+        id: ID,
+        options: StreamAddAckOptions,
+    ) -> RedisResult<Option<String>> {
+        add_ack_invocation(add_ack_script(), dest, items, key, group, id, options).invoke(self)
+    }
+
     // XCLAIM <key> <group> <consumer> <min-idle-time> [<ID-1> <ID-2>]
 
     /// Claim pending, unacked messages, after some period of time,
@@ -182,6 +332,111 @@ pub trait StreamCommands: ConnectionLike + Sized {
             .query(self)
     }
 
+    // XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+
+    /// Claim pending messages idle for longer than `min_idle_time`, scanning the
+    /// pending entries list from the `start` id. Requires Redis 6.2 or newer.
+    ///
+    /// This is the atomic alternative to combining `xpending_count` and `xclaim`.
+    /// Take note of the StreamAutoClaimReply return type.
+    ///
+    #[inline]
+    fn xautoclaim<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        C: ToRedisArgs,
+        MIT: ToRedisArgs,
+        S: ToRedisArgs,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S,
+    ) -> RedisResult<StreamAutoClaimReply> {
+        cmd("XAUTOCLAIM")
+            .arg(key)
+            .arg(group)
+            .arg(consumer)
+            .arg(min_idle_time)
+            .arg(start)
+            .query(self)
+    }
+
+    // XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+    //     [COUNT <count>] [JUSTID]
+
+    /// This is the optional arguments version of `xautoclaim`.
+    ///
+    /// ```no_run
+    /// use redis_streams::{client_open,Connection,RedisResult,StreamCommands,StreamAutoClaimOptions,StreamAutoClaimReply};
+    /// let client = client_open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// // Claim up to 10 messages idle for more than 60s
+    /// // for consumer "c2", starting at the beginning of the pending entries list.
+    ///
+    /// let opts = StreamAutoClaimOptions::default().count(10);
+    /// let reply: RedisResult<StreamAutoClaimReply> =
+    ///     con.xautoclaim_options("k1", "g1", "c2", 60000, "0-0", opts);
+    /// ```
+    ///
+    #[inline]
+    fn xautoclaim_options<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        C: ToRedisArgs,
+        MIT: ToRedisArgs,
+        S: ToRedisArgs,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S,
+        options: StreamAutoClaimOptions,
+    ) -> RedisResult<StreamAutoClaimReply> {
+        cmd("XAUTOCLAIM")
+            .arg(key)
+            .arg(group)
+            .arg(consumer)
+            .arg(min_idle_time)
+            .arg(start)
+            .arg(options)
+            .query(self)
+    }
+
+    /// Returns an iterator which calls `xautoclaim_options` from `0-0`,
+    /// following the cursor until Redis returns `0-0`.
+    /// Each item is one StreamAutoClaimReply page.
+    ///
+    /// ```no_run
+    /// use redis_streams::{client_open,StreamCommands,StreamAutoClaimOptions};
+    /// let client = client_open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// let opts = StreamAutoClaimOptions::default().count(100);
+    /// for page in con.xautoclaim_iter("k1", "g1", "c2", 60000, opts) {
+    ///     for msg in page.unwrap().claimed {
+    ///         println!("claimed {}", msg.id);
+    ///     }
+    /// }
+    /// ```
+    ///
+    #[inline]
+    fn xautoclaim_iter<K: ToRedisArgs, G: ToRedisArgs, C: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: usize,
+        options: StreamAutoClaimOptions,
+    ) -> StreamAutoClaimIter<'_, Self> {
+        StreamAutoClaimIter::new(self, key, group, consumer, min_idle_time, options)
+    }
+
     // XDEL <key> [<ID1> <ID2> ... <IDN>]
 
     /// Deletes a list of `id`s for a given stream `key`.
@@ -243,6 +498,78 @@ pub trait StreamCommands: ConnectionLike + Sized {
             .query(self)
     }
 
+    // XGROUP CREATE <key> <groupname> <id or $> [MKSTREAM] [ENTRIESREAD <entries-read>]
+
+    /// Create a consumer `group` with the MKSTREAM and ENTRIESREAD
+    /// args set from a `StreamGroupCreateOptions` builder.
+    ///
+    #[inline]
+    fn xgroup_create_options<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        ID: ToRedisArgs,
+        RV: FromRedisValue,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+        options: StreamGroupCreateOptions,
+    ) -> RedisResult<RV> {
+        cmd("XGROUP")
+            .arg("CREATE")
+            .arg(key)
+            .arg(group)
+            .arg(id)
+            .arg(options)
+            .query(self)
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $>
+
+    /// Create a consumer `group` unless it already exists.
+    /// Returns true if the group was created and false on `BUSYGROUP`.
+    ///
+    #[inline]
+    fn xgroup_create_if_missing<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> RedisResult<bool> {
+        ignore_busy_group(
+            cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .query(self),
+        )
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $> [MKSTREAM]
+
+    /// This is the alternate version of `xgroup_create_if_missing`
+    /// which makes the stream if it doesn't exist.
+    ///
+    #[inline]
+    fn xgroup_create_mkstream_if_missing<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> RedisResult<bool> {
+        ignore_busy_group(
+            cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .arg("MKSTREAM")
+                .query(self),
+        )
+    }
+
     // XGROUP SETID <key> <groupname> <id or $>
 
     /// Alter which `id` you want consumers to begin reading from an existing
@@ -263,6 +590,35 @@ pub trait StreamCommands: ConnectionLike + Sized {
             .query(self)
     }
 
+    // XGROUP SETID <key> <groupname> <id or $> ENTRIESREAD <entries-read>
+
+    /// Same as `xgroup_setid` but also sets how many entries the
+    /// group has read, so Redis can report its `lag`.
+    /// Requires Redis 7 or newer.
+    ///
+    #[inline]
+    fn xgroup_setid_entries_read<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        ID: ToRedisArgs,
+        RV: FromRedisValue,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+        entries_read: usize,
+    ) -> RedisResult<RV> {
+        cmd("XGROUP")
+            .arg("SETID")
+            .arg(key)
+            .arg(group)
+            .arg(id)
+            .arg("ENTRIESREAD")
+            .arg(entries_read)
+            .query(self)
+    }
+
     // XGROUP DESTROY <key> <groupname>
 
     /// Destroy an existing consumer `group` for a given stream `key`
@@ -296,14 +652,64 @@ pub trait StreamCommands: ConnectionLike + Sized {
             .query(self)
     }
 
+    // XGROUP CREATECONSUMER <key> <groupname> <consumername>
+
+    /// Create a `consumer` in an existing consumer `group` without
+    /// reading from it. Returns 1 if it was created or 0 if it already existed.
+    /// Requires Redis 6.2 or newer.
+    ///
+    #[inline]
+    fn xgroup_createconsumer<K: ToRedisArgs, G: ToRedisArgs, C: ToRedisArgs, RV: FromRedisValue>(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+    ) -> RedisResult<RV> {
+        cmd("XGROUP")
+            .arg("CREATECONSUMER")
+            .arg(key)
+            .arg(group)
+            .arg(consumer)
+            .query(self)
+    }
+
+    // XSETID <key> <id>
+
+    /// Set the last generated `id` of a stream `key`.
+    /// Useful when restoring a stream from a backup.
+    ///
+    #[inline]
+    fn xsetid<K: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(
+        &mut self,
+        key: K,
+        id: ID,
+    ) -> RedisResult<RV> {
+        cmd("XSETID").arg(key).arg(id).query(self)
+    }
+
+    // XSETID <key> <id> [ENTRIESADDED <entries-added>] [MAXDELETEDID <max-deleted-id>]
+
+    /// Same as `xsetid` with the ENTRIESADDED and MAXDELETEDID
+    /// args set from a `StreamSetIdOptions` builder.
+    ///
+    #[inline]
+    fn xsetid_options<K: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(
+        &mut self,
+        key: K,
+        id: ID,
+        options: StreamSetIdOptions,
+    ) -> RedisResult<RV> {
+        cmd("XSETID").arg(key).arg(id).arg(options).query(self)
+    }
+
     // XINFO CONSUMERS <key> <group>
 
     /// This returns all info details about
     /// which consumers have read messages for given consumer `group`.
     /// Take note of the StreamInfoConsumersReply return type.
     ///
-    /// *It's possible this return value might not contain new fields
-    /// added by Redis in future versions.*
+    /// Fields this crate doesn't parse, such as ones added by newer
+    /// Redis versions, are kept in the reply's `extra` map.
     ///
     #[inline]
     fn xinfo_consumers<K: ToRedisArgs, G: ToRedisArgs>(
@@ -323,8 +729,8 @@ pub trait StreamCommands: ConnectionLike + Sized {
     /// Returns all consumer `group`s created for a given stream `key`.
     /// Take note of the StreamInfoGroupsReply return type.
     ///
-    /// *It's possible this return value might not contain new fields
-    /// added by Redis in future versions.*
+    /// Fields this crate doesn't parse, such as ones added by newer
+    /// Redis versions, are kept in the reply's `extra` map.
     ///
     #[inline]
     fn xinfo_groups<K: ToRedisArgs>(&mut self, key: K) -> RedisResult<StreamInfoGroupsReply> {
@@ -337,27 +743,62 @@ pub trait StreamCommands: ConnectionLike + Sized {
     /// (first & last message `id`, length, number of groups, etc.)
     /// Take note of the StreamInfoStreamReply return type.
     ///
-    /// *It's possible this return value might not contain new fields
-    /// added by Redis in future versions.*
+    /// Fields this crate doesn't parse, such as ones added by newer
+    /// Redis versions, are kept in the reply's `extra` map.
     ///
     #[inline]
     fn xinfo_stream<K: ToRedisArgs>(&mut self, key: K) -> RedisResult<StreamInfoStreamReply> {
         cmd("XINFO").arg("STREAM").arg(key).query(self)
     }
 
-    // XLEN <key>
-    /// Returns the number of messages for a given stream `key`.
+    // XINFO STREAM <key> FULL
+
+    /// Returns the full stream state, including its entries, consumer groups,
+    /// group and consumer pending entries lists.
+    /// Redis limits the entries and pending lists to 10 items by default.
+    /// Take note of the StreamInfoStreamFullReply return type.
     ///
     #[inline]
-    fn xlen<K: ToRedisArgs, RV: FromRedisValue>(&mut self, key: K) -> RedisResult<RV> {
-        cmd("XLEN").arg(key).query(self)
+    fn xinfo_stream_full<K: ToRedisArgs>(
+        &mut self,
+        key: K,
+    ) -> RedisResult<StreamInfoStreamFullReply> {
+        cmd("XINFO").arg("STREAM").arg(key).arg("FULL").query(self)
     }
 
-    // XPENDING <key> <group> [<start> <stop> <count> [<consumer>]]
+    // XINFO STREAM <key> FULL COUNT <count>
 
-    /// This is a basic version of making XPENDING command calls which only
-    /// passes a stream `key` and consumer `group` and it
-    /// returns details about which consumers have pending messages
+    /// Same as `xinfo_stream_full` but limits the entries and pending lists
+    /// to `count` items. A `count` of 0 returns everything.
+    ///
+    #[inline]
+    fn xinfo_stream_full_count<K: ToRedisArgs, C: ToRedisArgs>(
+        &mut self,
+        key: K,
+        count: C,
+    ) -> RedisResult<StreamInfoStreamFullReply> {
+        cmd("XINFO")
+            .arg("STREAM")
+            .arg(key)
+            .arg("FULL")
+            .arg("COUNT")
+            .arg(count)
+            .query(self)
+    }
+
+    // XLEN <key>
+    /// Returns the number of messages for a given stream `key`.
+    ///
+    #[inline]
+    fn xlen<K: ToRedisArgs, RV: FromRedisValue>(&mut self, key: K) -> RedisResult<RV> {
+        cmd("XLEN").arg(key).query(self)
+    }
+
+    // XPENDING <key> <group> [<start> <stop> <count> [<consumer>]]
+
+    /// This is a basic version of making XPENDING command calls which only
+    /// passes a stream `key` and consumer `group` and it
+    /// returns details about which consumers have pending messages
     /// that haven't been acked.
     ///
     /// You can use this method along with
@@ -445,6 +886,34 @@ pub trait StreamCommands: ConnectionLike + Sized {
             .query(self)
     }
 
+    // XPENDING <key> <group> [IDLE <min-idle-time>] <start> <end> <count> [<consumer>]
+
+    /// An alternate version of `xpending_count` which takes a
+    /// `StreamPendingOptions` builder for the range, count, consumer
+    /// and IDLE filter.
+    ///
+    /// ```no_run
+    /// use redis_streams::{client_open,StreamCommands,StreamPendingOptions,StreamPendingCountReply};
+    /// let client = client_open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// // entries idle for more than a minute
+    /// let opts = StreamPendingOptions::default().idle(60000).count(50);
+    /// let reply: StreamPendingCountReply = con.xpending_options("k1", "g1", opts).unwrap();
+    /// ```
+    ///
+    /// Take note of the StreamPendingCountReply return type.
+    ///
+    #[inline]
+    fn xpending_options<K: ToRedisArgs, G: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        options: StreamPendingOptions,
+    ) -> RedisResult<StreamPendingCountReply> {
+        cmd("XPENDING").arg(key).arg(group).arg(options).query(self)
+    }
+
     // XRANGE key start end
 
     /// Returns a range of messages in a given stream `key`.
@@ -453,40 +922,90 @@ pub trait StreamCommands: ConnectionLike + Sized {
     /// Set `end` to `+` to end the most recent message.
     /// You can pass message `id` to both `start` and `end`.
     ///
-    /// Take note of the StreamRangeReply return type.
+    /// The return type is generic: annotate it as StreamRangeReply, or
+    /// StreamBytesRangeReply to keep non-utf8 fields intact. This is a
+    /// breaking change, since `let reply = con.xrange(..)?` used to infer
+    /// StreamRangeReply and now needs the annotation.
     ///
     #[inline]
-    fn xrange<K: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs>(
+    fn xrange<K: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs, RV: FromRedisValue>(
         &mut self,
         key: K,
         start: S,
         end: E,
-    ) -> RedisResult<StreamRangeReply> {
+    ) -> RedisResult<RV> {
         cmd("XRANGE").arg(key).arg(start).arg(end).query(self)
     }
 
     // XRANGE key - +
 
     /// A helper method for automatically returning all messages in a stream by `key`.
-    /// **Use with caution!**
+    /// **Use with caution!** `xrange_iter` walks the stream one page at a time instead.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
     ///
     #[inline]
     fn xrange_all<K: ToRedisArgs, RV: FromRedisValue>(&mut self, key: K) -> RedisResult<RV> {
         cmd("XRANGE").arg(key).arg("-").arg("+").query(self)
     }
 
+    /// Returns an iterator over the messages between the inclusive
+    /// `start` and `end` ids, fetching `count` messages per `XRANGE` call.
+    /// Use `StreamEntryId::MIN`/`MAX` for open bounds and
+    /// `StreamEntryId::from_system_time` for time bounds.
+    ///
+    /// ```no_run
+    /// use redis_streams::{client_open,StreamCommands,StreamEntryId};
+    /// let client = client_open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// for msg in con.xrange_iter("k1", StreamEntryId::MIN, StreamEntryId::MAX, 100) {
+    ///     println!("{}", msg.unwrap().id);
+    /// }
+    /// ```
+    ///
+    #[inline]
+    fn xrange_iter<K: ToRedisArgs>(
+        &mut self,
+        key: K,
+        start: StreamEntryId,
+        end: StreamEntryId,
+        count: usize,
+    ) -> StreamRangeIter<'_, Self> {
+        StreamRangeIter::new(self.xrange_page_iter(key, start, end, count))
+    }
+
+    /// Same as `xrange_iter`, but each item is one StreamRangeReply page.
+    ///
+    #[inline]
+    fn xrange_page_iter<K: ToRedisArgs>(
+        &mut self,
+        key: K,
+        start: StreamEntryId,
+        end: StreamEntryId,
+        count: usize,
+    ) -> StreamRangePageIter<'_, Self> {
+        StreamRangePageIter::new(self, key, start, end, count, false)
+    }
+
     // XRANGE key start end [COUNT <n>]
 
     /// A method for paginating a stream by `key`.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
     ///
     #[inline]
-    fn xrange_count<K: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs, C: ToRedisArgs>(
+    fn xrange_count<
+        K: ToRedisArgs,
+        S: ToRedisArgs,
+        E: ToRedisArgs,
+        C: ToRedisArgs,
+        RV: FromRedisValue,
+    >(
         &mut self,
         key: K,
         start: S,
         end: E,
         count: C,
-    ) -> RedisResult<StreamRangeReply> {
+    ) -> RedisResult<RV> {
         cmd("XRANGE")
             .arg(key)
             .arg(start)
@@ -503,12 +1022,16 @@ pub trait StreamCommands: ConnectionLike + Sized {
     /// For more advanced control, like blocking, limiting, or reading by consumer `group`,
     /// see `xread_options`.
     ///
+    /// The return type is generic: annotate it as StreamReadReply, or
+    /// StreamBytesReadReply to keep non-utf8 fields intact. Like `xrange`,
+    /// this no longer infers StreamReadReply on its own.
+    ///
     #[inline]
-    fn xread<K: ToRedisArgs, ID: ToRedisArgs>(
+    fn xread<K: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(
         &mut self,
         keys: &[K],
         ids: &[ID],
-    ) -> RedisResult<StreamReadReply> {
+    ) -> RedisResult<RV> {
         cmd("XREAD").arg("STREAMS").arg(keys).arg(ids).query(self)
     }
 
@@ -544,14 +1067,15 @@ pub trait StreamCommands: ConnectionLike + Sized {
     /// let results: RedisResult<StreamReadReply> =
     ///     con.xread_options(&["k1"], &[">"], opts);
     /// ```
+    /// Decodes as a StreamReadReply or StreamBytesReadReply.
     ///
     #[inline]
-    fn xread_options<K: ToRedisArgs, ID: ToRedisArgs>(
+    fn xread_options<K: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(
         &mut self,
         keys: &[K],
         ids: &[ID],
         options: StreamReadOptions,
-    ) -> RedisResult<StreamReadReply> {
+    ) -> RedisResult<RV> {
         cmd(if options.read_only() {
             "XREAD"
         } else {
@@ -568,14 +1092,15 @@ pub trait StreamCommands: ConnectionLike + Sized {
 
     /// This is the reverse version of `xrange`.
     /// The same rules apply for `start` and `end` here.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
     ///
     #[inline]
-    fn xrevrange<K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs>(
+    fn xrevrange<K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs, RV: FromRedisValue>(
         &mut self,
         key: K,
         end: E,
         start: S,
-    ) -> RedisResult<StreamRangeReply> {
+    ) -> RedisResult<RV> {
         cmd("XREVRANGE").arg(key).arg(end).arg(start).query(self)
     }
 
@@ -583,24 +1108,59 @@ pub trait StreamCommands: ConnectionLike + Sized {
 
     /// This is the reverse version of `xrange_all`.
     /// The same rules apply for `start` and `end` here.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
     ///
-    fn xrevrange_all<K: ToRedisArgs>(&mut self, key: K) -> RedisResult<StreamRangeReply> {
+    fn xrevrange_all<K: ToRedisArgs, RV: FromRedisValue>(&mut self, key: K) -> RedisResult<RV> {
         cmd("XREVRANGE").arg(key).arg("+").arg("-").query(self)
     }
 
+    /// This is the reverse version of `xrange_iter`, walking
+    /// from `end` down to `start`.
+    ///
+    #[inline]
+    fn xrevrange_iter<K: ToRedisArgs>(
+        &mut self,
+        key: K,
+        end: StreamEntryId,
+        start: StreamEntryId,
+        count: usize,
+    ) -> StreamRangeIter<'_, Self> {
+        StreamRangeIter::new(self.xrevrange_page_iter(key, end, start, count))
+    }
+
+    /// This is the reverse version of `xrange_page_iter`.
+    ///
+    #[inline]
+    fn xrevrange_page_iter<K: ToRedisArgs>(
+        &mut self,
+        key: K,
+        end: StreamEntryId,
+        start: StreamEntryId,
+        count: usize,
+    ) -> StreamRangePageIter<'_, Self> {
+        StreamRangePageIter::new(self, key, start, end, count, true)
+    }
+
     // XREVRANGE key end start [COUNT <n>]
 
     /// This is the reverse version of `xrange_count`.
     /// The same rules apply for `start` and `end` here.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
     ///
     #[inline]
-    fn xrevrange_count<K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs, C: ToRedisArgs>(
+    fn xrevrange_count<
+        K: ToRedisArgs,
+        E: ToRedisArgs,
+        S: ToRedisArgs,
+        C: ToRedisArgs,
+        RV: FromRedisValue,
+    >(
         &mut self,
         key: K,
         end: E,
         start: S,
         count: C,
-    ) -> RedisResult<StreamRangeReply> {
+    ) -> RedisResult<RV> {
         cmd("XREVRANGE")
             .arg(key)
             .arg(end)
@@ -622,6 +1182,2086 @@ pub trait StreamCommands: ConnectionLike + Sized {
     ) -> RedisResult<RV> {
         cmd("XTRIM").arg(key).arg(maxlen).query(self)
     }
+
+    // XTRIM <key> <MAXLEN | MINID> [= | ~] threshold [LIMIT count]
+
+    /// Trim a stream `key` by MAXLEN or MINID, optionally bounding
+    /// the work done with LIMIT. Returns the number of evicted messages.
+    ///
+    #[inline]
+    fn xtrim_options<K: ToRedisArgs, RV: FromRedisValue>(
+        &mut self,
+        key: K,
+        options: StreamTrimOptions,
+    ) -> RedisResult<RV> {
+        cmd("XTRIM").arg(key).arg(options).query(self)
+    }
 }
 
 impl<T> StreamCommands for T where T: ConnectionLike {}
+
+/// Async version of [`StreamCommands`] for `redis::aio` connections.
+///
+/// Every method mirrors its blocking counterpart and returns the same
+/// reply types, wrapped in a `RedisFuture`.
+///
+/// ```no_run
+/// use redis_streams::{client_open,AsyncStreamCommands,RedisResult,StreamReadOptions,StreamReadReply};
+/// # async fn run() -> RedisResult<()> {
+/// let client = client_open("redis://127.0.0.1/0")?;
+/// let mut con = client.get_async_connection().await?;
+///
+/// let _: String = con.xadd("k1", "*", &[("hello", "world")]).await?;
+///
+/// let opts = StreamReadOptions::default().count(10);
+/// let reply: StreamReadReply = con.xread_options(&["k1"], &["0"], opts).await?;
+/// # Ok(()) }
+/// ```
+///
+/// [`StreamCommands`]: ./trait.StreamCommands.html
+///
+pub trait AsyncStreamCommands: AsyncConnectionLike + Send + Sized {
+    // XACK <key> <group> <id> <id> ... <id>
+
+    /// Ack pending stream messages checked out by a consumer.
+    ///
+    #[inline]
+    fn xack<'a, K, G, ID, RV>(&'a mut self, key: K, group: G, ids: &'a [ID]) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XACK")
+                .arg(key)
+                .arg(group)
+                .arg(ids)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key <ID or *> [field value] [field value] ...
+
+    /// Add a stream message by `key`. Use `*` as the `id` for the current timestamp.
+    ///
+    #[inline]
+    fn xadd<'a, K, ID, F, V, RV>(
+        &'a mut self,
+        key: K,
+        id: ID,
+        items: &'a [(F, V)],
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        F: ToRedisArgs + Send + Sync + 'a,
+        V: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg(items)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key <ID or *> [rust BTreeMap] ...
+
+    /// BTreeMap variant for adding a stream message by `key`.
+    /// Use `*` as the `id` for the current timestamp.
+    ///
+    #[inline]
+    fn xadd_map<'a, K, ID, BTM, RV>(&'a mut self, key: K, id: ID, map: BTM) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        BTM: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg(map)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key <ID or *> [serde struct] ...
+
+    /// Serde variant for adding a stream message by `key`.
+    /// The `message` struct (or map) is flattened to field/value pairs.
+    /// Use `*` as the `id` for the current timestamp.
+    ///
+    #[cfg(feature = "serde")]
+    #[inline]
+    fn xadd_serialize<'a, K, ID, T, RV>(
+        &'a mut self,
+        key: K,
+        id: ID,
+        message: &T,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        T: Serialize + ?Sized,
+        RV: FromRedisValue,
+    {
+        let items = to_stream_fields(message);
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg(items?)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key <ID or *> [StreamMessage] ...
+
+    /// `StreamMessage` variant for adding a stream message by `key`.
+    /// Use `*` as the `id` for the current timestamp.
+    ///
+    #[inline]
+    fn xadd_message<'a, K, ID, M, RV>(
+        &'a mut self,
+        key: K,
+        id: ID,
+        message: &M,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        M: StreamMessage,
+        RV: FromRedisValue,
+    {
+        let items = message.to_stream_fields();
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg(items?)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key [MAXLEN [~|=] <count>] <ID or *> [field value] [field value] ...
+
+    /// Add a stream message while capping the stream at a maxlength.
+    ///
+    #[inline]
+    fn xadd_maxlen<'a, K, ID, F, V, RV>(
+        &'a mut self,
+        key: K,
+        maxlen: StreamMaxlen,
+        id: ID,
+        items: &'a [(F, V)],
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        F: ToRedisArgs + Send + Sync + 'a,
+        V: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(maxlen)
+                .arg(id)
+                .arg(items)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key [MAXLEN [~|=] <count>] <ID or *> [rust BTreeMap] ...
+
+    /// BTreeMap variant for adding a stream message while capping the stream at a maxlength.
+    ///
+    #[inline]
+    fn xadd_maxlen_map<'a, K, ID, BTM, RV>(
+        &'a mut self,
+        key: K,
+        maxlen: StreamMaxlen,
+        id: ID,
+        map: BTM,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        BTM: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(maxlen)
+                .arg(id)
+                .arg(map)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key [NOMKSTREAM] [<MAXLEN | MINID> [= | ~] threshold [LIMIT count]]
+    //     <ID or *> [field value] [field value] ...
+
+    /// Add a stream message with the optional arguments set by `StreamAddOptions`.
+    /// See `StreamCommands::xadd_options` for details.
+    ///
+    #[inline]
+    fn xadd_options<'a, K, F, V, RV>(
+        &'a mut self,
+        key: K,
+        items: &'a [(F, V)],
+        options: StreamAddOptions,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        F: ToRedisArgs + Send + Sync + 'a,
+        V: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(options)
+                .arg(items)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD key [NOMKSTREAM] [<MAXLEN | MINID> [= | ~] threshold [LIMIT count]]
+    //     <ID or *> [rust BTreeMap] ...
+
+    /// BTreeMap variant of `xadd_options`.
+    ///
+    #[inline]
+    fn xadd_options_map<'a, K, BTM, RV>(
+        &'a mut self,
+        key: K,
+        map: BTM,
+        options: StreamAddOptions,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        BTM: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XADD")
+                .arg(key)
+                .arg(options)
+                .arg(map)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XADD <dest> ... + XACK <key> <group> <id> [+ XDEL <key> <id>]
+
+    /// Atomically add a message to the `dest` stream and ack `id` from
+    /// the source stream `key`, optionally deleting it from the source.
+    /// See `StreamCommands::xadd_xack` for details.
+    ///
+    #[inline]
+    fn xadd_xack<'a, DK, F, V, K, G, ID>(
+        &'a mut self,
+        dest: DK,
+        items: &'a [(F, V)],
+        key: K,
+        group: G,
+        id: ID,
+        options: StreamAddAckOptions,
+    ) -> RedisFuture<'a, Option<String>>
+    where
+        DK: ToRedisArgs + Send + Sync + 'a,
+        F: ToRedisArgs + Send + Sync + 'a,
+        V: ToRedisArgs + Send + Sync + 'a,
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            add_ack_invocation(add_ack_script(), dest, items, key, group, id, options)
+                .invoke_async(self)
+                .await
+        })
+    }
+
+    // XCLAIM <key> <group> <consumer> <min-idle-time> [<ID-1> <ID-2>]
+
+    /// Claim pending, unacked messages, after some period of time,
+    /// currently checked out by another consumer.
+    ///
+    /// This method only accepts the must-have arguments for claiming messages.
+    /// If optional arguments are required, see `xclaim_options` below.
+    ///
+    #[inline]
+    fn xclaim<'a, K, G, C, MIT, ID>(
+        &'a mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        ids: &'a [ID],
+    ) -> RedisFuture<'a, StreamClaimReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        MIT: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(ids)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XCLAIM <key> <group> <consumer> <min-idle-time> <ID-1> <ID-2>
+    //     [IDLE <milliseconds>] [TIME <mstime>] [RETRYCOUNT <count>]
+    //     [FORCE] [JUSTID]
+
+    /// This is the optional arguments version for claiming unacked, pending messages
+    /// currently checked out by another consumer.
+    /// See `StreamCommands::xclaim_options` for details.
+    ///
+    #[inline]
+    fn xclaim_options<'a, K, G, C, MIT, ID, RV>(
+        &'a mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        ids: &'a [ID],
+        options: StreamClaimOptions,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        MIT: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(ids)
+                .arg(options)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+
+    /// Claim pending messages idle for longer than `min_idle_time`, scanning the
+    /// pending entries list from the `start` id. Requires Redis 6.2 or newer.
+    ///
+    #[inline]
+    fn xautoclaim<'a, K, G, C, MIT, S>(
+        &'a mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S,
+    ) -> RedisFuture<'a, StreamAutoClaimReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        MIT: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XAUTOCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(start)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+    //     [COUNT <count>] [JUSTID]
+
+    /// This is the optional arguments version of `xautoclaim`.
+    ///
+    #[inline]
+    fn xautoclaim_options<'a, K, G, C, MIT, S>(
+        &'a mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S,
+        options: StreamAutoClaimOptions,
+    ) -> RedisFuture<'a, StreamAutoClaimReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        MIT: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XAUTOCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(start)
+                .arg(options)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XDEL <key> [<ID1> <ID2> ... <IDN>]
+
+    /// Deletes a list of `id`s for a given stream `key`.
+    ///
+    #[inline]
+    fn xdel<'a, K, ID, RV>(&'a mut self, key: K, ids: &'a [ID]) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move { cmd("XDEL").arg(key).arg(ids).query_async(self).await })
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $>
+
+    /// This command is used for creating a consumer `group`. It expects the stream key
+    /// to already exist. Otherwise, use `xgroup_create_mkstream` if it doesn't.
+    ///
+    #[inline]
+    fn xgroup_create<'a, K, G, ID, RV>(
+        &'a mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $> [MKSTREAM]
+
+    /// This is the alternate version for creating a consumer `group`
+    /// which makes the stream if it doesn't exist.
+    ///
+    #[inline]
+    fn xgroup_create_mkstream<'a, K, G, ID, RV>(
+        &'a mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .arg("MKSTREAM")
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $> [MKSTREAM] [ENTRIESREAD <entries-read>]
+
+    /// Create a consumer `group` with the MKSTREAM and ENTRIESREAD
+    /// args set from a `StreamGroupCreateOptions` builder.
+    ///
+    #[inline]
+    fn xgroup_create_options<'a, K, G, ID, RV>(
+        &'a mut self,
+        key: K,
+        group: G,
+        id: ID,
+        options: StreamGroupCreateOptions,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .arg(options)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $>
+
+    /// Create a consumer `group` unless it already exists.
+    /// Returns true if the group was created and false on `BUSYGROUP`.
+    ///
+    #[inline]
+    fn xgroup_create_if_missing<'a, K, G, ID>(
+        &'a mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> RedisFuture<'a, bool>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            ignore_busy_group(
+                cmd("XGROUP")
+                    .arg("CREATE")
+                    .arg(key)
+                    .arg(group)
+                    .arg(id)
+                    .query_async(self)
+                    .await,
+            )
+        })
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $> [MKSTREAM]
+
+    /// This is the alternate version of `xgroup_create_if_missing`
+    /// which makes the stream if it doesn't exist.
+    ///
+    #[inline]
+    fn xgroup_create_mkstream_if_missing<'a, K, G, ID>(
+        &'a mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> RedisFuture<'a, bool>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            ignore_busy_group(
+                cmd("XGROUP")
+                    .arg("CREATE")
+                    .arg(key)
+                    .arg(group)
+                    .arg(id)
+                    .arg("MKSTREAM")
+                    .query_async(self)
+                    .await,
+            )
+        })
+    }
+
+    // XGROUP SETID <key> <groupname> <id or $>
+
+    /// Alter which `id` you want consumers to begin reading from an existing
+    /// consumer `group`.
+    ///
+    #[inline]
+    fn xgroup_setid<'a, K, G, ID, RV>(&'a mut self, key: K, group: G, id: ID) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("SETID")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XGROUP SETID <key> <groupname> <id or $> ENTRIESREAD <entries-read>
+
+    /// Same as `xgroup_setid` but also sets how many entries the
+    /// group has read, so Redis can report its `lag`.
+    ///
+    #[inline]
+    fn xgroup_setid_entries_read<'a, K, G, ID, RV>(
+        &'a mut self,
+        key: K,
+        group: G,
+        id: ID,
+        entries_read: usize,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("SETID")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .arg("ENTRIESREAD")
+                .arg(entries_read)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XGROUP DESTROY <key> <groupname>
+
+    /// Destroy an existing consumer `group` for a given stream `key`
+    ///
+    #[inline]
+    fn xgroup_destroy<'a, K, G, RV>(&'a mut self, key: K, group: G) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("DESTROY")
+                .arg(key)
+                .arg(group)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XGROUP DELCONSUMER <key> <groupname> <consumername>
+
+    /// This deletes a `consumer` from an existing consumer `group`
+    /// for given stream `key.
+    ///
+    #[inline]
+    fn xgroup_delconsumer<'a, K, G, C, RV>(
+        &'a mut self,
+        key: K,
+        group: G,
+        consumer: C,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("DELCONSUMER")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XGROUP CREATECONSUMER <key> <groupname> <consumername>
+
+    /// Create a `consumer` in an existing consumer `group` without
+    /// reading from it. Returns 1 if it was created or 0 if it already existed.
+    ///
+    #[inline]
+    fn xgroup_createconsumer<'a, K, G, C, RV>(
+        &'a mut self,
+        key: K,
+        group: G,
+        consumer: C,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XGROUP")
+                .arg("CREATECONSUMER")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XSETID <key> <id>
+
+    /// Set the last generated `id` of a stream `key`.
+    ///
+    #[inline]
+    fn xsetid<'a, K, ID, RV>(&'a mut self, key: K, id: ID) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move { cmd("XSETID").arg(key).arg(id).query_async(self).await })
+    }
+
+    // XSETID <key> <id> [ENTRIESADDED <entries-added>] [MAXDELETEDID <max-deleted-id>]
+
+    /// Same as `xsetid` with the ENTRIESADDED and MAXDELETEDID
+    /// args set from a `StreamSetIdOptions` builder.
+    ///
+    #[inline]
+    fn xsetid_options<'a, K, ID, RV>(
+        &'a mut self,
+        key: K,
+        id: ID,
+        options: StreamSetIdOptions,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XSETID")
+                .arg(key)
+                .arg(id)
+                .arg(options)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XINFO CONSUMERS <key> <group>
+
+    /// This returns all info details about
+    /// which consumers have read messages for given consumer `group`.
+    /// Take note of the StreamInfoConsumersReply return type.
+    ///
+    /// Fields this crate doesn't parse, such as ones added by newer
+    /// Redis versions, are kept in the reply's `extra` map.
+    ///
+    #[inline]
+    fn xinfo_consumers<'a, K, G>(
+        &'a mut self,
+        key: K,
+        group: G,
+    ) -> RedisFuture<'a, StreamInfoConsumersReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XINFO")
+                .arg("CONSUMERS")
+                .arg(key)
+                .arg(group)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XINFO GROUPS <key>
+
+    /// Returns all consumer `group`s created for a given stream `key`.
+    /// Take note of the StreamInfoGroupsReply return type.
+    ///
+    /// Fields this crate doesn't parse, such as ones added by newer
+    /// Redis versions, are kept in the reply's `extra` map.
+    ///
+    #[inline]
+    fn xinfo_groups<'a, K>(&'a mut self, key: K) -> RedisFuture<'a, StreamInfoGroupsReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move { cmd("XINFO").arg("GROUPS").arg(key).query_async(self).await })
+    }
+
+    // XINFO STREAM <key>
+
+    /// Returns info about high-level stream details
+    /// (first & last message `id`, length, number of groups, etc.)
+    /// Take note of the StreamInfoStreamReply return type.
+    ///
+    /// Fields this crate doesn't parse, such as ones added by newer
+    /// Redis versions, are kept in the reply's `extra` map.
+    ///
+    #[inline]
+    fn xinfo_stream<'a, K>(&'a mut self, key: K) -> RedisFuture<'a, StreamInfoStreamReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move { cmd("XINFO").arg("STREAM").arg(key).query_async(self).await })
+    }
+
+    // XINFO STREAM <key> FULL
+
+    /// Returns the full stream state, including its entries, consumer groups,
+    /// group and consumer pending entries lists.
+    /// See `StreamCommands::xinfo_stream_full` for details.
+    ///
+    #[inline]
+    fn xinfo_stream_full<'a, K>(&'a mut self, key: K) -> RedisFuture<'a, StreamInfoStreamFullReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XINFO")
+                .arg("STREAM")
+                .arg(key)
+                .arg("FULL")
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XINFO STREAM <key> FULL COUNT <count>
+
+    /// Same as `xinfo_stream_full` but limits the entries and pending lists
+    /// to `count` items. A `count` of 0 returns everything.
+    ///
+    #[inline]
+    fn xinfo_stream_full_count<'a, K, C>(
+        &'a mut self,
+        key: K,
+        count: C,
+    ) -> RedisFuture<'a, StreamInfoStreamFullReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XINFO")
+                .arg("STREAM")
+                .arg(key)
+                .arg("FULL")
+                .arg("COUNT")
+                .arg(count)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XLEN <key>
+
+    /// Returns the number of messages for a given stream `key`.
+    ///
+    #[inline]
+    fn xlen<'a, K, RV>(&'a mut self, key: K) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move { cmd("XLEN").arg(key).query_async(self).await })
+    }
+
+    // XPENDING <key> <group> [<start> <stop> <count> [<consumer>]]
+
+    /// This is a basic version of making XPENDING command calls which only
+    /// passes a stream `key` and consumer `group` and it
+    /// returns details about which consumers have pending messages
+    /// that haven't been acked.
+    ///
+    /// Take note of the StreamPendingReply return type.
+    ///
+    #[inline]
+    fn xpending<'a, K, G>(&'a mut self, key: K, group: G) -> RedisFuture<'a, StreamPendingReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move { cmd("XPENDING").arg(key).arg(group).query_async(self).await })
+    }
+
+    // XPENDING <key> <group> <start> <stop> <count>
+
+    /// This XPENDING version returns a list of all messages over the range.
+    /// You can use this for paginating pending messages (but without the message HashMap).
+    ///
+    /// Take note of the StreamPendingCountReply return type.
+    ///
+    #[inline]
+    fn xpending_count<'a, K, G, S, E, C>(
+        &'a mut self,
+        key: K,
+        group: G,
+        start: S,
+        end: E,
+        count: C,
+    ) -> RedisFuture<'a, StreamPendingCountReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+        E: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XPENDING")
+                .arg(key)
+                .arg(group)
+                .arg(start)
+                .arg(end)
+                .arg(count)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XPENDING <key> <group> <start> <stop> <count> <consumer>
+
+    /// An alternate version of `xpending_count` which filters by `consumer` name.
+    ///
+    /// Take note of the StreamPendingCountReply return type.
+    ///
+    #[inline]
+    fn xpending_consumer_count<'a, K, G, S, E, C, CN>(
+        &'a mut self,
+        key: K,
+        group: G,
+        start: S,
+        end: E,
+        count: C,
+        consumer: CN,
+    ) -> RedisFuture<'a, StreamPendingCountReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+        E: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        CN: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XPENDING")
+                .arg(key)
+                .arg(group)
+                .arg(start)
+                .arg(end)
+                .arg(count)
+                .arg(consumer)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XPENDING <key> <group> [IDLE <min-idle-time>] <start> <end> <count> [<consumer>]
+
+    /// An alternate version of `xpending_count` which takes a
+    /// `StreamPendingOptions` builder for the range, count, consumer
+    /// and IDLE filter.
+    ///
+    /// Take note of the StreamPendingCountReply return type.
+    ///
+    #[inline]
+    fn xpending_options<'a, K, G>(
+        &'a mut self,
+        key: K,
+        group: G,
+        options: StreamPendingOptions,
+    ) -> RedisFuture<'a, StreamPendingCountReply>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        G: ToRedisArgs + Send + Sync + 'a,
+    {
+        Box::pin(async move {
+            cmd("XPENDING")
+                .arg(key)
+                .arg(group)
+                .arg(options)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XRANGE key start end
+
+    /// Returns a range of messages in a given stream `key`.
+    ///
+    /// The return type is generic: annotate it as StreamRangeReply, or
+    /// StreamBytesRangeReply to keep non-utf8 fields intact. This is a
+    /// breaking change, since `let reply = con.xrange(..)?` used to infer
+    /// StreamRangeReply and now needs the annotation.
+    ///
+    #[inline]
+    fn xrange<'a, K, S, E, RV>(&'a mut self, key: K, start: S, end: E) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+        E: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XRANGE")
+                .arg(key)
+                .arg(start)
+                .arg(end)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XRANGE key - +
+
+    /// A helper method for automatically returning all messages in a stream by `key`.
+    /// **Use with caution!**
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
+    ///
+    #[inline]
+    fn xrange_all<'a, K, RV>(&'a mut self, key: K) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XRANGE")
+                .arg(key)
+                .arg("-")
+                .arg("+")
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XRANGE key start end [COUNT <n>]
+
+    /// A method for paginating a stream by `key`.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
+    ///
+    #[inline]
+    fn xrange_count<'a, K, S, E, C, RV>(
+        &'a mut self,
+        key: K,
+        start: S,
+        end: E,
+        count: C,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+        E: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XRANGE")
+                .arg(key)
+                .arg(start)
+                .arg(end)
+                .arg("COUNT")
+                .arg(count)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XREAD STREAMS key_1 key_2 ... key_N ID_1 ID_2 ... ID_N
+
+    /// Read a list of `id`s for each stream `key`.
+    /// This is the basic form of reading streams.
+    /// For more advanced control, like blocking, limiting, or reading by consumer `group`,
+    /// see `xread_options`.
+    ///
+    /// The return type is generic: annotate it as StreamReadReply, or
+    /// StreamBytesReadReply to keep non-utf8 fields intact. Like `xrange`,
+    /// this no longer infers StreamReadReply on its own.
+    ///
+    #[inline]
+    fn xread<'a, K, ID, RV>(&'a mut self, keys: &'a [K], ids: &'a [ID]) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XREAD")
+                .arg("STREAMS")
+                .arg(keys)
+                .arg(ids)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XREAD [BLOCK <milliseconds>] [COUNT <count>]
+    //       STREAMS key_1 key_2 ... key_N
+    //       ID_1 ID_2 ... ID_N
+    // XREADGROUP [BLOCK <milliseconds>] [COUNT <count>] [NOACK] [GROUP group-name consumer-name]
+    //       STREAMS key_1 key_2 ... key_N
+    //       ID_1 ID_2 ... ID_N
+
+    /// This method handles setting optional arguments for
+    /// `XREAD` or `XREADGROUP` Redis commands.
+    /// See `StreamCommands::xread_options` for details.
+    /// Decodes as a StreamReadReply or StreamBytesReadReply.
+    ///
+    #[inline]
+    fn xread_options<'a, K, ID, RV>(
+        &'a mut self,
+        keys: &'a [K],
+        ids: &'a [ID],
+        options: StreamReadOptions,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        ID: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd(if options.read_only() {
+                "XREAD"
+            } else {
+                "XREADGROUP"
+            })
+            .arg(options)
+            .arg("STREAMS")
+            .arg(keys)
+            .arg(ids)
+            .query_async(self)
+            .await
+        })
+    }
+
+    // XREVRANGE key end start
+
+    /// This is the reverse version of `xrange`.
+    /// The same rules apply for `start` and `end` here.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
+    ///
+    #[inline]
+    fn xrevrange<'a, K, E, S, RV>(&'a mut self, key: K, end: E, start: S) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        E: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XREVRANGE")
+                .arg(key)
+                .arg(end)
+                .arg(start)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XREVRANGE key + -
+
+    /// This is the reverse version of `xrange_all`.
+    /// The same rules apply for `start` and `end` here.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
+    ///
+    #[inline]
+    fn xrevrange_all<'a, K, RV>(&'a mut self, key: K) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XREVRANGE")
+                .arg(key)
+                .arg("+")
+                .arg("-")
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XREVRANGE key end start [COUNT <n>]
+
+    /// This is the reverse version of `xrange_count`.
+    /// The same rules apply for `start` and `end` here.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
+    ///
+    #[inline]
+    fn xrevrange_count<'a, K, E, S, C, RV>(
+        &'a mut self,
+        key: K,
+        end: E,
+        start: S,
+        count: C,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        E: ToRedisArgs + Send + Sync + 'a,
+        S: ToRedisArgs + Send + Sync + 'a,
+        C: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move {
+            cmd("XREVRANGE")
+                .arg(key)
+                .arg(end)
+                .arg(start)
+                .arg("COUNT")
+                .arg(count)
+                .query_async(self)
+                .await
+        })
+    }
+
+    // XTRIM <key> MAXLEN [~|=] <count>  (Same as XADD MAXLEN option)
+
+    /// Trim a stream `key` to a MAXLEN count.
+    ///
+    #[inline]
+    fn xtrim<'a, K, RV>(&'a mut self, key: K, maxlen: StreamMaxlen) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move { cmd("XTRIM").arg(key).arg(maxlen).query_async(self).await })
+    }
+
+    // XTRIM <key> <MAXLEN | MINID> [= | ~] threshold [LIMIT count]
+
+    /// Trim a stream `key` by MAXLEN or MINID, optionally bounding
+    /// the work done with LIMIT. Returns the number of evicted messages.
+    ///
+    #[inline]
+    fn xtrim_options<'a, K, RV>(
+        &'a mut self,
+        key: K,
+        options: StreamTrimOptions,
+    ) -> RedisFuture<'a, RV>
+    where
+        K: ToRedisArgs + Send + Sync + 'a,
+        RV: FromRedisValue,
+    {
+        Box::pin(async move { cmd("XTRIM").arg(key).arg(options).query_async(self).await })
+    }
+}
+
+impl<T> AsyncStreamCommands for T where T: AsyncConnectionLike + Send + Sized {}
+
+/// Pipeline version of [`StreamCommands`] for batching stream commands
+/// into a single round trip, or a MULTI/EXEC block with `atomic()`.
+///
+/// Every method queues its command on the pipeline and takes the same
+/// arguments and option builders as its connection counterpart.
+/// The replies are decoded together when the pipeline is queried,
+/// so pick the crate's reply types when destructuring the result.
+///
+/// ```no_run
+/// use redis_streams::{client_open,RedisResult,StreamPipelineCommands,StreamRangeReply};
+/// # fn run() -> RedisResult<()> {
+/// let client = client_open("redis://127.0.0.1/0")?;
+/// let mut con = client.get_connection()?;
+///
+/// let (acked, id, reply): (usize, String, StreamRangeReply) = redis::pipe()
+///     .atomic()
+///     .xack("k1", "g1", &["1000-0"])
+///     .xadd("k2", "*", &[("hello", "world")])
+///     .xrange_count("k2", "-", "+", 10)
+///     .query(&mut con)?;
+/// # Ok(()) }
+/// ```
+///
+/// [`StreamCommands`]: ./trait.StreamCommands.html
+///
+pub trait StreamPipelineCommands: Sized {
+    /// Queue a command on the pipeline.
+    ///
+    fn perform(&mut self, cmd: &Cmd) -> &mut Self;
+
+    // XACK <key> <group> <id> <id> ... <id>
+
+    /// Ack pending stream messages checked out by a consumer.
+    ///
+    #[inline]
+    fn xack<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        ids: &[ID],
+    ) -> &mut Self {
+        self.perform(cmd("XACK").arg(key).arg(group).arg(ids))
+    }
+
+    // XADD key <ID or *> [field value] [field value] ...
+
+    /// Add a stream message by `key`. Use `*` as the `id` for the current timestamp.
+    ///
+    #[inline]
+    fn xadd<K: ToRedisArgs, ID: ToRedisArgs, F: ToRedisArgs, V: ToRedisArgs>(
+        &mut self,
+        key: K,
+        id: ID,
+        items: &[(F, V)],
+    ) -> &mut Self {
+        self.perform(cmd("XADD").arg(key).arg(id).arg(items))
+    }
+
+    // XADD key <ID or *> [rust BTreeMap] ...
+
+    /// BTreeMap variant for adding a stream message by `key`.
+    ///
+    #[inline]
+    fn xadd_map<K: ToRedisArgs, ID: ToRedisArgs, BTM: ToRedisArgs>(
+        &mut self,
+        key: K,
+        id: ID,
+        map: BTM,
+    ) -> &mut Self {
+        self.perform(cmd("XADD").arg(key).arg(id).arg(map))
+    }
+
+    // XADD key <ID or *> [serde struct] ...
+
+    /// Serde variant for adding a stream message by `key`.
+    /// Returns an error without queueing anything if `message` can't be flattened.
+    ///
+    #[cfg(feature = "serde")]
+    #[inline]
+    fn xadd_serialize<K: ToRedisArgs, ID: ToRedisArgs, T: Serialize + ?Sized>(
+        &mut self,
+        key: K,
+        id: ID,
+        message: &T,
+    ) -> RedisResult<&mut Self> {
+        let items = to_stream_fields(message)?;
+        Ok(self.perform(cmd("XADD").arg(key).arg(id).arg(items)))
+    }
+
+    // XADD key <ID or *> [StreamMessage] ...
+
+    /// `StreamMessage` variant for adding a stream message by `key`.
+    /// Returns an error without queueing anything if `message` can't be encoded.
+    ///
+    #[inline]
+    fn xadd_message<K: ToRedisArgs, ID: ToRedisArgs, M: StreamMessage>(
+        &mut self,
+        key: K,
+        id: ID,
+        message: &M,
+    ) -> RedisResult<&mut Self> {
+        let items = message.to_stream_fields()?;
+        Ok(self.perform(cmd("XADD").arg(key).arg(id).arg(items)))
+    }
+
+    // XADD key [MAXLEN [~|=] <count>] <ID or *> [field value] [field value] ...
+
+    /// Add a stream message while capping the stream at a maxlength.
+    ///
+    #[inline]
+    fn xadd_maxlen<K: ToRedisArgs, ID: ToRedisArgs, F: ToRedisArgs, V: ToRedisArgs>(
+        &mut self,
+        key: K,
+        maxlen: StreamMaxlen,
+        id: ID,
+        items: &[(F, V)],
+    ) -> &mut Self {
+        self.perform(cmd("XADD").arg(key).arg(maxlen).arg(id).arg(items))
+    }
+
+    // XADD key [MAXLEN [~|=] <count>] <ID or *> [rust BTreeMap] ...
+
+    /// BTreeMap variant for adding a stream message while capping the stream at a maxlength.
+    ///
+    #[inline]
+    fn xadd_maxlen_map<K: ToRedisArgs, ID: ToRedisArgs, BTM: ToRedisArgs>(
+        &mut self,
+        key: K,
+        maxlen: StreamMaxlen,
+        id: ID,
+        map: BTM,
+    ) -> &mut Self {
+        self.perform(cmd("XADD").arg(key).arg(maxlen).arg(id).arg(map))
+    }
+
+    // XADD key [NOMKSTREAM] [<MAXLEN | MINID> [= | ~] threshold [LIMIT count]]
+    //     <ID or *> [field value] [field value] ...
+
+    /// Add a stream message with the optional arguments set by `StreamAddOptions`.
+    ///
+    #[inline]
+    fn xadd_options<K: ToRedisArgs, F: ToRedisArgs, V: ToRedisArgs>(
+        &mut self,
+        key: K,
+        items: &[(F, V)],
+        options: StreamAddOptions,
+    ) -> &mut Self {
+        self.perform(cmd("XADD").arg(key).arg(options).arg(items))
+    }
+
+    // XADD key [NOMKSTREAM] [<MAXLEN | MINID> [= | ~] threshold [LIMIT count]]
+    //     <ID or *> [rust BTreeMap] ...
+
+    /// BTreeMap variant of `xadd_options`.
+    ///
+    #[inline]
+    fn xadd_options_map<K: ToRedisArgs, BTM: ToRedisArgs>(
+        &mut self,
+        key: K,
+        map: BTM,
+        options: StreamAddOptions,
+    ) -> &mut Self {
+        self.perform(cmd("XADD").arg(key).arg(options).arg(map))
+    }
+
+    // XCLAIM <key> <group> <consumer> <min-idle-time> [<ID-1> <ID-2>]
+
+    /// Claim pending, unacked messages, after some period of time,
+    /// currently checked out by another consumer.
+    /// Decodes as a StreamClaimReply.
+    ///
+    #[inline]
+    fn xclaim<K: ToRedisArgs, G: ToRedisArgs, C: ToRedisArgs, MIT: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        ids: &[ID],
+    ) -> &mut Self {
+        self.perform(
+            cmd("XCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(ids),
+        )
+    }
+
+    // XCLAIM <key> <group> <consumer> <min-idle-time> <ID-1> <ID-2>
+    //     [IDLE <milliseconds>] [TIME <mstime>] [RETRYCOUNT <count>]
+    //     [FORCE] [JUSTID]
+
+    /// This is the optional arguments version for claiming unacked, pending messages.
+    /// Decodes as a StreamClaimReply, or a list of ids with JUSTID.
+    ///
+    #[inline]
+    fn xclaim_options<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        C: ToRedisArgs,
+        MIT: ToRedisArgs,
+        ID: ToRedisArgs,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        ids: &[ID],
+        options: StreamClaimOptions,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(ids)
+                .arg(options),
+        )
+    }
+
+    // XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+
+    /// Claim pending messages idle for longer than `min_idle_time`.
+    /// Decodes as a StreamAutoClaimReply.
+    ///
+    #[inline]
+    fn xautoclaim<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        C: ToRedisArgs,
+        MIT: ToRedisArgs,
+        S: ToRedisArgs,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XAUTOCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(start),
+        )
+    }
+
+    // XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+    //     [COUNT <count>] [JUSTID]
+
+    /// This is the optional arguments version of `xautoclaim`.
+    ///
+    #[inline]
+    fn xautoclaim_options<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        C: ToRedisArgs,
+        MIT: ToRedisArgs,
+        S: ToRedisArgs,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S,
+        options: StreamAutoClaimOptions,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XAUTOCLAIM")
+                .arg(key)
+                .arg(group)
+                .arg(consumer)
+                .arg(min_idle_time)
+                .arg(start)
+                .arg(options),
+        )
+    }
+
+    // XDEL <key> [<ID1> <ID2> ... <IDN>]
+
+    /// Deletes a list of `id`s for a given stream `key`.
+    ///
+    #[inline]
+    fn xdel<K: ToRedisArgs, ID: ToRedisArgs>(&mut self, key: K, ids: &[ID]) -> &mut Self {
+        self.perform(cmd("XDEL").arg(key).arg(ids))
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $>
+
+    /// Create a consumer `group` for an existing stream `key`.
+    ///
+    #[inline]
+    fn xgroup_create<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> &mut Self {
+        self.perform(cmd("XGROUP").arg("CREATE").arg(key).arg(group).arg(id))
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $> [MKSTREAM]
+
+    /// Create a consumer `group`, making the stream if it doesn't exist.
+    ///
+    #[inline]
+    fn xgroup_create_mkstream<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .arg("MKSTREAM"),
+        )
+    }
+
+    // XGROUP CREATE <key> <groupname> <id or $> [MKSTREAM] [ENTRIESREAD <entries-read>]
+
+    /// Create a consumer `group` with the args set from a `StreamGroupCreateOptions` builder.
+    ///
+    #[inline]
+    fn xgroup_create_options<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+        options: StreamGroupCreateOptions,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .arg(options),
+        )
+    }
+
+    // XGROUP SETID <key> <groupname> <id or $>
+
+    /// Alter which `id` you want consumers to begin reading from an existing
+    /// consumer `group`.
+    ///
+    #[inline]
+    fn xgroup_setid<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+    ) -> &mut Self {
+        self.perform(cmd("XGROUP").arg("SETID").arg(key).arg(group).arg(id))
+    }
+
+    // XGROUP SETID <key> <groupname> <id or $> ENTRIESREAD <entries-read>
+
+    /// Same as `xgroup_setid` but also sets how many entries the group has read.
+    ///
+    #[inline]
+    fn xgroup_setid_entries_read<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        id: ID,
+        entries_read: usize,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XGROUP")
+                .arg("SETID")
+                .arg(key)
+                .arg(group)
+                .arg(id)
+                .arg("ENTRIESREAD")
+                .arg(entries_read),
+        )
+    }
+
+    // XGROUP DESTROY <key> <groupname>
+
+    /// Destroy an existing consumer `group` for a given stream `key`
+    ///
+    #[inline]
+    fn xgroup_destroy<K: ToRedisArgs, G: ToRedisArgs>(&mut self, key: K, group: G) -> &mut Self {
+        self.perform(cmd("XGROUP").arg("DESTROY").arg(key).arg(group))
+    }
+
+    // XGROUP DELCONSUMER <key> <groupname> <consumername>
+
+    /// This deletes a `consumer` from an existing consumer `group`
+    /// for given stream `key.
+    ///
+    #[inline]
+    fn xgroup_delconsumer<K: ToRedisArgs, G: ToRedisArgs, C: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XGROUP")
+                .arg("DELCONSUMER")
+                .arg(key)
+                .arg(group)
+                .arg(consumer),
+        )
+    }
+
+    // XGROUP CREATECONSUMER <key> <groupname> <consumername>
+
+    /// Create a `consumer` in an existing consumer `group` without reading from it.
+    ///
+    #[inline]
+    fn xgroup_createconsumer<K: ToRedisArgs, G: ToRedisArgs, C: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        consumer: C,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XGROUP")
+                .arg("CREATECONSUMER")
+                .arg(key)
+                .arg(group)
+                .arg(consumer),
+        )
+    }
+
+    // XSETID <key> <id>
+
+    /// Set the last generated `id` of a stream `key`.
+    ///
+    #[inline]
+    fn xsetid<K: ToRedisArgs, ID: ToRedisArgs>(&mut self, key: K, id: ID) -> &mut Self {
+        self.perform(cmd("XSETID").arg(key).arg(id))
+    }
+
+    // XSETID <key> <id> [ENTRIESADDED <entries-added>] [MAXDELETEDID <max-deleted-id>]
+
+    /// Same as `xsetid` with the args set from a `StreamSetIdOptions` builder.
+    ///
+    #[inline]
+    fn xsetid_options<K: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        key: K,
+        id: ID,
+        options: StreamSetIdOptions,
+    ) -> &mut Self {
+        self.perform(cmd("XSETID").arg(key).arg(id).arg(options))
+    }
+
+    // XINFO CONSUMERS <key> <group>
+
+    /// Queue consumer info for a given consumer `group`.
+    /// Decodes as a StreamInfoConsumersReply.
+    ///
+    #[inline]
+    fn xinfo_consumers<K: ToRedisArgs, G: ToRedisArgs>(&mut self, key: K, group: G) -> &mut Self {
+        self.perform(cmd("XINFO").arg("CONSUMERS").arg(key).arg(group))
+    }
+
+    // XINFO GROUPS <key>
+
+    /// Queue consumer group info for a stream `key`.
+    /// Decodes as a StreamInfoGroupsReply.
+    ///
+    #[inline]
+    fn xinfo_groups<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.perform(cmd("XINFO").arg("GROUPS").arg(key))
+    }
+
+    // XINFO STREAM <key>
+
+    /// Queue stream info for a stream `key`.
+    /// Decodes as a StreamInfoStreamReply.
+    ///
+    #[inline]
+    fn xinfo_stream<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.perform(cmd("XINFO").arg("STREAM").arg(key))
+    }
+
+    // XINFO STREAM <key> FULL
+
+    /// Queue the full stream state for a stream `key`.
+    /// Decodes as a StreamInfoStreamFullReply.
+    ///
+    #[inline]
+    fn xinfo_stream_full<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.perform(cmd("XINFO").arg("STREAM").arg(key).arg("FULL"))
+    }
+
+    // XINFO STREAM <key> FULL COUNT <count>
+
+    /// Same as `xinfo_stream_full` but limits the entries and pending lists
+    /// to `count` items.
+    ///
+    #[inline]
+    fn xinfo_stream_full_count<K: ToRedisArgs, C: ToRedisArgs>(
+        &mut self,
+        key: K,
+        count: C,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XINFO")
+                .arg("STREAM")
+                .arg(key)
+                .arg("FULL")
+                .arg("COUNT")
+                .arg(count),
+        )
+    }
+
+    // XLEN <key>
+
+    /// Returns the number of messages for a given stream `key`.
+    ///
+    #[inline]
+    fn xlen<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.perform(cmd("XLEN").arg(key))
+    }
+
+    // XPENDING <key> <group>
+
+    /// Queue the pending summary for a consumer `group`.
+    /// Decodes as a StreamPendingReply.
+    ///
+    #[inline]
+    fn xpending<K: ToRedisArgs, G: ToRedisArgs>(&mut self, key: K, group: G) -> &mut Self {
+        self.perform(cmd("XPENDING").arg(key).arg(group))
+    }
+
+    // XPENDING <key> <group> <start> <stop> <count>
+
+    /// Queue a list of pending messages over the range.
+    /// Decodes as a StreamPendingCountReply.
+    ///
+    #[inline]
+    fn xpending_count<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        S: ToRedisArgs,
+        E: ToRedisArgs,
+        C: ToRedisArgs,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        start: S,
+        end: E,
+        count: C,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XPENDING")
+                .arg(key)
+                .arg(group)
+                .arg(start)
+                .arg(end)
+                .arg(count),
+        )
+    }
+
+    // XPENDING <key> <group> <start> <stop> <count> <consumer>
+
+    /// An alternate version of `xpending_count` which filters by `consumer` name.
+    ///
+    #[inline]
+    fn xpending_consumer_count<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        S: ToRedisArgs,
+        E: ToRedisArgs,
+        C: ToRedisArgs,
+        CN: ToRedisArgs,
+    >(
+        &mut self,
+        key: K,
+        group: G,
+        start: S,
+        end: E,
+        count: C,
+        consumer: CN,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XPENDING")
+                .arg(key)
+                .arg(group)
+                .arg(start)
+                .arg(end)
+                .arg(count)
+                .arg(consumer),
+        )
+    }
+
+    // XPENDING <key> <group> [IDLE <min-idle-time>] <start> <end> <count> [<consumer>]
+
+    /// An alternate version of `xpending_count` which takes a
+    /// `StreamPendingOptions` builder.
+    ///
+    #[inline]
+    fn xpending_options<K: ToRedisArgs, G: ToRedisArgs>(
+        &mut self,
+        key: K,
+        group: G,
+        options: StreamPendingOptions,
+    ) -> &mut Self {
+        self.perform(cmd("XPENDING").arg(key).arg(group).arg(options))
+    }
+
+    // XRANGE key start end
+
+    /// Queue a range of messages in a given stream `key`.
+    /// Decodes as a StreamRangeReply or StreamBytesRangeReply.
+    ///
+    #[inline]
+    fn xrange<K: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs>(
+        &mut self,
+        key: K,
+        start: S,
+        end: E,
+    ) -> &mut Self {
+        self.perform(cmd("XRANGE").arg(key).arg(start).arg(end))
+    }
+
+    // XRANGE key - +
+
+    /// Queue all messages in a stream by `key`.
+    /// **Use with caution!**
+    ///
+    #[inline]
+    fn xrange_all<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.perform(cmd("XRANGE").arg(key).arg("-").arg("+"))
+    }
+
+    // XRANGE key start end [COUNT <n>]
+
+    /// A method for paginating a stream by `key`.
+    ///
+    #[inline]
+    fn xrange_count<K: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs, C: ToRedisArgs>(
+        &mut self,
+        key: K,
+        start: S,
+        end: E,
+        count: C,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XRANGE")
+                .arg(key)
+                .arg(start)
+                .arg(end)
+                .arg("COUNT")
+                .arg(count),
+        )
+    }
+
+    // XREAD STREAMS key_1 key_2 ... key_N ID_1 ID_2 ... ID_N
+
+    /// Read a list of `id`s for each stream `key`.
+    /// Decodes as a StreamReadReply or StreamBytesReadReply.
+    ///
+    #[inline]
+    fn xread<K: ToRedisArgs, ID: ToRedisArgs>(&mut self, keys: &[K], ids: &[ID]) -> &mut Self {
+        self.perform(cmd("XREAD").arg("STREAMS").arg(keys).arg(ids))
+    }
+
+    // XREAD [BLOCK <milliseconds>] [COUNT <count>]
+    //       STREAMS key_1 key_2 ... key_N
+    //       ID_1 ID_2 ... ID_N
+    // XREADGROUP [BLOCK <milliseconds>] [COUNT <count>] [NOACK] [GROUP group-name consumer-name]
+    //       STREAMS key_1 key_2 ... key_N
+    //       ID_1 ID_2 ... ID_N
+
+    /// This method handles setting optional arguments for
+    /// `XREAD` or `XREADGROUP` Redis commands.
+    /// Avoid BLOCK here since it holds up the whole pipeline.
+    ///
+    #[inline]
+    fn xread_options<K: ToRedisArgs, ID: ToRedisArgs>(
+        &mut self,
+        keys: &[K],
+        ids: &[ID],
+        options: StreamReadOptions,
+    ) -> &mut Self {
+        self.perform(
+            cmd(if options.read_only() {
+                "XREAD"
+            } else {
+                "XREADGROUP"
+            })
+            .arg(options)
+            .arg("STREAMS")
+            .arg(keys)
+            .arg(ids),
+        )
+    }
+
+    // XREVRANGE key end start
+
+    /// This is the reverse version of `xrange`.
+    ///
+    #[inline]
+    fn xrevrange<K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs>(
+        &mut self,
+        key: K,
+        end: E,
+        start: S,
+    ) -> &mut Self {
+        self.perform(cmd("XREVRANGE").arg(key).arg(end).arg(start))
+    }
+
+    // XREVRANGE key + -
+
+    /// This is the reverse version of `xrange_all`.
+    ///
+    #[inline]
+    fn xrevrange_all<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.perform(cmd("XREVRANGE").arg(key).arg("+").arg("-"))
+    }
+
+    // XREVRANGE key end start [COUNT <n>]
+
+    /// This is the reverse version of `xrange_count`.
+    ///
+    #[inline]
+    fn xrevrange_count<K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs, C: ToRedisArgs>(
+        &mut self,
+        key: K,
+        end: E,
+        start: S,
+        count: C,
+    ) -> &mut Self {
+        self.perform(
+            cmd("XREVRANGE")
+                .arg(key)
+                .arg(end)
+                .arg(start)
+                .arg("COUNT")
+                .arg(count),
+        )
+    }
+
+    // XTRIM <key> MAXLEN [~|=] <count>  (Same as XADD MAXLEN option)
+
+    /// Trim a stream `key` to a MAXLEN count.
+    ///
+    #[inline]
+    fn xtrim<K: ToRedisArgs>(&mut self, key: K, maxlen: StreamMaxlen) -> &mut Self {
+        self.perform(cmd("XTRIM").arg(key).arg(maxlen))
+    }
+
+    // XTRIM <key> <MAXLEN | MINID> [= | ~] threshold [LIMIT count]
+
+    /// Trim a stream `key` by MAXLEN or MINID.
+    ///
+    #[inline]
+    fn xtrim_options<K: ToRedisArgs>(&mut self, key: K, options: StreamTrimOptions) -> &mut Self {
+        self.perform(cmd("XTRIM").arg(key).arg(options))
+    }
+}
+
+impl StreamPipelineCommands for Pipeline {
+    #[inline]
+    fn perform(&mut self, cmd: &Cmd) -> &mut Self {
+        self.add_command(cmd.clone())
+    }
+}
@@ -0,0 +1,217 @@
+use crate::commands::StreamCommands;
+use crate::types::{StreamId, StreamReadOptions, StreamReadReply};
+
+use redis::{ConnectionLike, RedisResult};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Cloneable signal for stopping a running [`StreamConsumer`].
+///
+/// [`StreamConsumer`]: ./struct.StreamConsumer.html
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamConsumerShutdown {
+    flag: Arc<AtomicBool>,
+}
+
+impl StreamConsumerShutdown {
+    /// Ask the consumer to stop after its current read.
+    pub fn shutdown(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+}
+
+type ErrorCallback<E> = Box<dyn FnMut(&str, &StreamId, E) + Send>;
+
+/// A managed consumer `group` worker.
+///
+/// `StreamConsumer` makes sure the consumer `group` exists for each stream `key`,
+/// drains the pending messages already delivered to this `consumer`,
+/// and then loops over `XREADGROUP` for new messages.
+/// Each message is passed to the handler: messages the handler returns `Ok` for
+/// are acked and messages it returns `Err` for are left pending and
+/// passed to the `on_error` callback with the error.
+///
+/// Shutdown is only checked between reads, so with a `BLOCK` timeout
+/// `run` can take up to that long to return after it's signaled.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamConsumer,StreamReadOptions};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let con = client.get_connection().unwrap();
+///
+/// let mut consumer = StreamConsumer::new(con, &["k1"], "g1", "c1", |key, msg| {
+///     println!("{} {:?}", key, msg.id);
+///     Ok::<(), String>(())
+/// })
+/// .read_options(StreamReadOptions::default().block(1000).count(10))
+/// .on_error(|key, msg, err| eprintln!("{} {:?} failed: {}", key, msg.id, err));
+///
+/// // Stop the consumer from another thread with `shutdown.shutdown()`.
+/// let shutdown = consumer.shutdown_handle();
+/// consumer.run().unwrap();
+/// ```
+///
+pub struct StreamConsumer<C, H, E> {
+    con: C,
+    keys: Vec<String>,
+    group: String,
+    consumer: String,
+    /// The `id` used when the consumer `group` is created.
+    start_id: String,
+    options: StreamReadOptions,
+    handler: H,
+    on_error: Option<ErrorCallback<E>>,
+    shutdown: StreamConsumerShutdown,
+}
+
+impl<C, H, E> StreamConsumer<C, H, E>
+where
+    C: ConnectionLike,
+    H: FnMut(&str, &StreamId) -> Result<(), E>,
+{
+    pub fn new(con: C, keys: &[&str], group: &str, consumer: &str, handler: H) -> Self {
+        StreamConsumer {
+            con,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            group: group.to_string(),
+            consumer: consumer.to_string(),
+            start_id: "$".to_string(),
+            options: StreamReadOptions::default(),
+            handler,
+            on_error: None,
+            shutdown: StreamConsumerShutdown::default(),
+        }
+    }
+
+    /// Set the `BLOCK` and `COUNT` options used for each read.
+    /// The `GROUP` option is always set by the consumer.
+    ///
+    /// Without a `BLOCK` timeout, `run` polls Redis in a tight loop.
+    pub fn read_options(mut self, options: StreamReadOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the `id` the consumer `group` starts reading from when it
+    /// doesn't already exist. Defaults to `$`.
+    pub fn start_id(mut self, id: &str) -> Self {
+        self.start_id = id.to_string();
+        self
+    }
+
+    /// Set the callback for errors returned by the handler.
+    /// The failed message is left pending either way.
+    pub fn on_error<F>(mut self, on_error: F) -> Self
+    where
+        F: FnMut(&str, &StreamId, E) + Send + 'static,
+    {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Returns a handle which stops `run` from another thread.
+    /// Shutdown is checked between reads, so a blocked read finishes first.
+    pub fn shutdown_handle(&self) -> StreamConsumerShutdown {
+        self.shutdown.clone()
+    }
+
+    /// Returns the underlying connection.
+    pub fn into_inner(self) -> C {
+        self.con
+    }
+
+    /// Create the consumer `group` for each stream `key`,
+    /// making the stream if it doesn't exist.
+    /// Groups which already exist are left as-is.
+    pub fn ensure_groups(&mut self) -> RedisResult<()> {
+        for key in &self.keys {
+            self.con
+                .xgroup_create_mkstream_if_missing(key, &self.group, &self.start_id)?;
+        }
+        Ok(())
+    }
+
+    /// Process the messages already delivered to this `consumer` but never acked.
+    /// Messages which fail again stay pending and aren't retried by this call.
+    /// Returns the number of messages passed to the handler.
+    pub fn drain_pending(&mut self) -> RedisResult<usize> {
+        let mut ids: Vec<String> = self.keys.iter().map(|_| "0".to_string()).collect();
+        let mut handled = 0;
+        loop {
+            let reply: StreamReadReply =
+                self.con
+                    .xread_options(&self.keys, &ids, self.group_options())?;
+            let mut seen = 0;
+            for stream_key in &reply.keys {
+                if let Some(last) = stream_key.ids.last() {
+                    if let Some(pos) = self.keys.iter().position(|k| k == &stream_key.key) {
+                        ids[pos] = last.id.to_string();
+                    }
+                }
+                seen += stream_key.ids.len();
+            }
+            if seen == 0 {
+                return Ok(handled);
+            }
+            handled += self.dispatch(&reply)?;
+        }
+    }
+
+    /// Read and process one batch of new messages.
+    /// Returns the number of messages passed to the handler.
+    pub fn read_once(&mut self) -> RedisResult<usize> {
+        let ids: Vec<&str> = self.keys.iter().map(|_| ">").collect();
+        let reply: StreamReadReply =
+            self.con
+                .xread_options(&self.keys, &ids, self.group_options())?;
+        self.dispatch(&reply)
+    }
+
+    /// Ensure the consumer `group`s exist, drain pending messages
+    /// and then process new messages until shutdown is signaled.
+    ///
+    /// Returns the first Redis error. Handler errors go to `on_error`
+    /// and don't stop the loop.
+    pub fn run(&mut self) -> RedisResult<()> {
+        self.ensure_groups()?;
+        self.drain_pending()?;
+        while !self.shutdown.is_shutdown() {
+            self.read_once()?;
+        }
+        Ok(())
+    }
+
+    // Redis doesn't block when reading pending messages (any id other than `>`),
+    // so the same options work for both reads.
+    fn group_options(&self) -> StreamReadOptions {
+        self.options.clone().group(&self.group, &self.consumer)
+    }
+
+    fn dispatch(&mut self, reply: &StreamReadReply) -> RedisResult<usize> {
+        let mut handled = 0;
+        for stream_key in &reply.keys {
+            let mut acks = vec![];
+            for stream_id in &stream_key.ids {
+                handled += 1;
+                match (self.handler)(&stream_key.key, stream_id) {
+                    Ok(()) => acks.push(stream_id.id),
+                    Err(err) => {
+                        if let Some(on_error) = self.on_error.as_mut() {
+                            on_error(&stream_key.key, stream_id, err);
+                        }
+                    }
+                }
+            }
+            if !acks.is_empty() {
+                let _: usize = self.con.xack(&stream_key.key, &self.group, &acks)?;
+            }
+        }
+        Ok(handled)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::commands::StreamCommands;
+use crate::types::{StreamRangeReply, StreamReadOptions, StreamReadReply};
+
+use redis::{ConnectionLike, RedisResult};
+
+use std::collections::BTreeMap;
+
+/// Tracks the last read `id` of each stream `key` across `XREAD` calls.
+///
+/// `keys` and `ids` build the parallel arguments for `xread` and
+/// `xread_options` and `update` advances the cursor from the reply.
+/// `read` does all three. With a consumer `group`, `>` ids are left as
+/// they are. The cursor can be saved to and loaded from a Redis hash
+/// mapping each key to its id.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamCursor,StreamReadOptions};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut con = client.get_connection().unwrap();
+///
+/// let mut cursor = StreamCursor::load(&mut con, "k1:cursor").unwrap();
+/// if cursor.get("k1").is_none() {
+///     cursor.insert("k1", "0");
+/// }
+///
+/// let opts = StreamReadOptions::default().block(1000).count(100);
+/// let reply = cursor.read(&mut con, opts).unwrap();
+/// for key in reply.keys {
+///     println!("{} read {} messages", key.key, key.ids.len());
+/// }
+/// cursor.save(&mut con, "k1:cursor").unwrap();
+/// ```
+///
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct StreamCursor {
+    ids: BTreeMap<String, String>,
+}
+
+impl StreamCursor {
+    pub fn new() -> Self {
+        StreamCursor::default()
+    }
+
+    /// Start tracking `key` from `id`, replacing its current id.
+    pub fn insert(&mut self, key: &str, id: &str) {
+        self.ids.insert(key.to_string(), id.to_string());
+    }
+
+    /// Stop tracking `key`. Returns its last id.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.ids.remove(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.ids.get(key).map(|id| id.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The `keys` argument for the next read.
+    pub fn keys(&self) -> Vec<&str> {
+        self.ids.keys().map(|key| key.as_str()).collect()
+    }
+
+    /// The `ids` argument for the next read, in the same order as `keys`.
+    pub fn ids(&self) -> Vec<&str> {
+        self.ids.values().map(|id| id.as_str()).collect()
+    }
+
+    /// Advance each key to the last message returned for it.
+    /// Keys which aren't tracked are ignored.
+    pub fn update(&mut self, reply: &StreamReadReply) {
+        for stream_key in &reply.keys {
+            if let (Some(id), Some(last)) =
+                (self.ids.get_mut(&stream_key.key), stream_key.ids.last())
+            {
+                if id != ">" {
+                    *id = last.id.to_string();
+                }
+            }
+        }
+    }
+
+    /// Read every tracked key with `xread_options` and `update` the cursor.
+    ///
+    /// `$` ids are first replaced with the key's last id, so messages
+    /// added between reads aren't skipped. Returns an empty reply
+    /// without calling Redis if no keys are tracked.
+    pub fn read<C: ConnectionLike>(
+        &mut self,
+        con: &mut C,
+        options: StreamReadOptions,
+    ) -> RedisResult<StreamReadReply> {
+        if self.ids.is_empty() {
+            return Ok(StreamReadReply::default());
+        }
+        for key in self.latest_keys() {
+            let reply: StreamRangeReply = con.xrevrange_count(&key, "+", "-", 1)?;
+            self.resolve_latest(&key, &reply);
+        }
+        let reply: StreamReadReply = con.xread_options(&self.keys(), &self.ids(), options)?;
+        self.update(&reply);
+        Ok(reply)
+    }
+
+    /// Keys whose id is still `$`.
+    pub(crate) fn latest_keys(&self) -> Vec<String> {
+        self.ids
+            .iter()
+            .filter(|(_, id)| *id == "$")
+            .map(|(key, _)| key.to_string())
+            .collect()
+    }
+
+    /// Replace the `$` id of `key` with the last id from
+    /// `XREVRANGE key + - COUNT 1`, or `0-0` if the stream is empty.
+    pub(crate) fn resolve_latest(&mut self, key: &str, reply: &StreamRangeReply) {
+        if let Some(id) = self.ids.get_mut(key) {
+            *id = match reply.ids.first() {
+                Some(last) => last.id.to_string(),
+                None => "0-0".to_string(),
+            };
+        }
+    }
+
+    /// Replace the hash at `hash_key` with the cursor's key/id pairs.
+    pub fn save<C: ConnectionLike>(&self, con: &mut C, hash_key: &str) -> RedisResult<()> {
+        let mut pipe = redis::pipe();
+        pipe.atomic().del(hash_key).ignore();
+        if !self.ids.is_empty() {
+            let items: Vec<(&String, &String)> = self.ids.iter().collect();
+            pipe.hset_multiple(hash_key, &items).ignore();
+        }
+        pipe.query(con)
+    }
+
+    /// Load a cursor saved with `save`.
+    /// A missing hash loads as an empty cursor.
+    pub fn load<C: ConnectionLike>(con: &mut C, hash_key: &str) -> RedisResult<Self> {
+        let ids: BTreeMap<String, String> = redis::cmd("HGETALL").arg(hash_key).query(con)?;
+        Ok(StreamCursor { ids })
+    }
+}
@@ -0,0 +1,191 @@
+use crate::commands::{StreamCommands, StreamPipelineCommands};
+use crate::types::{
+    StreamAddAckOptions, StreamAddOptions, StreamBytesRangeReply, StreamEntryId,
+    StreamPendingCountReply, StreamPendingId, StreamTrimOptions,
+};
+
+use redis::{from_redis_value, ConnectionLike, RedisResult};
+
+/// Moves poison messages out of a consumer `group` into a dead-letter stream.
+///
+/// `route` scans the group's pending entries list and, for each message
+/// delivered more than `max_deliveries` times, copies its fields to the
+/// dead-letter stream and acks it in the source `group`. Both happen in
+/// one atomic `xadd_xack` call. The copied message gets extra metadata
+/// fields (see the `*_FIELD` consts) so it can be inspected or pushed back
+/// to the source stream with `redrive`.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamDeadLetter};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut con = client.get_connection().unwrap();
+///
+/// let dead_letter = StreamDeadLetter::new("k1", "g1", "k1:dlq").max_deliveries(3);
+///
+/// // Run periodically, e.g. next to a `StreamConsumer`.
+/// let routed = dead_letter.route(&mut con).unwrap();
+///
+/// // Once the bug is fixed, push the messages back to "k1".
+/// let redriven = dead_letter.redrive(&mut con, 100).unwrap();
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct StreamDeadLetter {
+    key: String,
+    group: String,
+    dead_letter_key: String,
+    max_deliveries: usize,
+    count: usize,
+    trim: Option<StreamTrimOptions>,
+}
+
+impl StreamDeadLetter {
+    /// The source stream `key`.
+    pub const KEY_FIELD: &'static str = "dlq-key";
+    /// The message `id` in the source stream.
+    pub const ID_FIELD: &'static str = "dlq-id";
+    /// The `consumer` the message was last delivered to.
+    pub const CONSUMER_FIELD: &'static str = "dlq-consumer";
+    /// How many times the message was delivered.
+    pub const DELIVERIES_FIELD: &'static str = "dlq-deliveries";
+
+    pub fn new(key: &str, group: &str, dead_letter_key: &str) -> Self {
+        StreamDeadLetter {
+            key: key.to_string(),
+            group: group.to_string(),
+            dead_letter_key: dead_letter_key.to_string(),
+            max_deliveries: 5,
+            count: 100,
+            trim: None,
+        }
+    }
+
+    /// Dead-letter messages delivered more than `n` times. Defaults to 5.
+    pub fn max_deliveries(mut self, n: usize) -> Self {
+        self.max_deliveries = n;
+        self
+    }
+
+    /// Set how many pending entries are fetched per `XPENDING` call.
+    /// Defaults to 100.
+    pub fn count(mut self, n: usize) -> Self {
+        self.count = n;
+        self
+    }
+
+    /// Trim the dead-letter stream each time a message is added to it.
+    pub fn trim<T: Into<StreamTrimOptions>>(mut self, trim: T) -> Self {
+        self.trim = Some(trim.into());
+        self
+    }
+
+    /// Scan the whole pending entries list once and dead-letter every message
+    /// delivered more than `max_deliveries` times.
+    /// Messages already deleted from the source stream are only acked.
+    /// Returns the number of messages removed from the pending entries list.
+    pub fn route<C: ConnectionLike>(&self, con: &mut C) -> RedisResult<usize> {
+        let mut start = StreamEntryId::MIN;
+        let mut routed = 0;
+        loop {
+            let reply: StreamPendingCountReply =
+                con.xpending_count(&self.key, &self.group, start, "+", self.count)?;
+            for pending in &reply.ids {
+                if pending.times_delivered > self.max_deliveries
+                    && self.route_pending(con, pending)?
+                {
+                    routed += 1;
+                }
+            }
+            match reply.ids.last().and_then(|pending| pending.id.next()) {
+                Some(next) if reply.ids.len() >= self.count => start = next,
+                _ => return Ok(routed),
+            }
+        }
+    }
+
+    /// Push up to `count` of the oldest dead-lettered messages back to their
+    /// source stream with a new `id`, without the metadata fields.
+    /// Each message is added and removed from the dead-letter stream in one
+    /// `MULTI`/`EXEC` block. Returns the number of messages redriven.
+    pub fn redrive<C: ConnectionLike>(&self, con: &mut C, count: usize) -> RedisResult<usize> {
+        let reply: StreamBytesRangeReply =
+            con.xrange_count(&self.dead_letter_key, "-", "+", count)?;
+        for message in &reply.ids {
+            let mut key = self.key.as_bytes().to_vec();
+            let mut fields = vec![];
+            for (field, value) in &message.fields {
+                let value: Vec<u8> = from_redis_value(value)?;
+                if field == Self::KEY_FIELD.as_bytes() {
+                    key = value;
+                } else if !is_metadata(field) {
+                    fields.push((field.clone(), value));
+                }
+            }
+            let _: (String, usize) = redis::pipe()
+                .atomic()
+                .xadd(key, "*", &fields)
+                .xdel(&self.dead_letter_key, &[message.id])
+                .query(con)?;
+        }
+        Ok(reply.ids.len())
+    }
+
+    pub(crate) fn is_source(&self, key: &str, group: &str) -> bool {
+        self.key == key && self.group == group
+    }
+
+    pub(crate) fn route_pending<C: ConnectionLike>(
+        &self,
+        con: &mut C,
+        pending: &StreamPendingId,
+    ) -> RedisResult<bool> {
+        let reply: StreamBytesRangeReply = con.xrange(&self.key, pending.id, pending.id)?;
+        let message = match reply.ids.first() {
+            Some(message) => message,
+            None => {
+                let acked: usize = con.xack(&self.key, &self.group, &[pending.id])?;
+                return Ok(acked > 0);
+            }
+        };
+
+        let mut fields: Vec<(Vec<u8>, Vec<u8>)> = vec![];
+        for (field, value) in &message.fields {
+            fields.push((field.clone(), from_redis_value(value)?));
+        }
+        fields.push((Self::KEY_FIELD.into(), self.key.clone().into_bytes()));
+        fields.push((Self::ID_FIELD.into(), pending.id.to_string().into_bytes()));
+        fields.push((
+            Self::CONSUMER_FIELD.into(),
+            pending.consumer.clone().into_bytes(),
+        ));
+        fields.push((
+            Self::DELIVERIES_FIELD.into(),
+            pending.times_delivered.to_string().into_bytes(),
+        ));
+
+        let mut add = StreamAddOptions::default();
+        if let Some(trim) = self.trim {
+            add = add.trim(trim);
+        }
+        let id = con.xadd_xack(
+            &self.dead_letter_key,
+            &fields,
+            &self.key,
+            &self.group,
+            pending.id,
+            StreamAddAckOptions::default().add_options(add),
+        )?;
+        Ok(id.is_some())
+    }
+}
+
+fn is_metadata(field: &[u8]) -> bool {
+    [
+        StreamDeadLetter::KEY_FIELD,
+        StreamDeadLetter::ID_FIELD,
+        StreamDeadLetter::CONSUMER_FIELD,
+        StreamDeadLetter::DELIVERIES_FIELD,
+    ]
+    .iter()
+    .any(|name| name.as_bytes() == field)
+}
@@ -0,0 +1,172 @@
+use crate::commands::StreamCommands;
+use crate::dead_letter::StreamDeadLetter;
+use crate::types::{
+    StreamEntryId, StreamId, StreamPendingCountReply, StreamPendingOptions, StreamReadReply,
+};
+
+use redis::{ConnectionLike, ErrorKind, RedisError, RedisResult};
+
+/// What happens when a `Delivery` is dropped without being settled.
+/// The message stays pending in every case.
+#[derive(Debug, Clone, Copy)]
+pub enum DeliveryDropBehavior {
+    /// Do nothing.
+    Ignore,
+    /// Log a warning with the `log` crate.
+    Log,
+    /// Pass the warning to a function, e.g. to count or report it.
+    Callback(fn(&str)),
+    /// Panic in debug builds and log a warning like `Log` in release
+    /// builds. Nothing happens if the delivery is dropped while the
+    /// thread is already panicking.
+    Panic,
+}
+
+/// A message read by a `consumer` of a `group`, which has to be
+/// settled with `ack`, `nack`, `dead_letter` or `defer`.
+///
+/// Dropping an unsettled delivery leaves the message pending and
+/// triggers the configured `DeliveryDropBehavior`.
+///
+/// ```no_run
+/// use redis_streams::{client_open,Delivery,StreamCommands,StreamReadOptions,StreamReadReply};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut con = client.get_connection().unwrap();
+///
+/// let opts = StreamReadOptions::default().group("g1", "c1");
+/// let reply: StreamReadReply = con.xread_options(&["k1"], &[">"], opts).unwrap();
+///
+/// for delivery in Delivery::from_reply(reply, "g1", "c1") {
+///     if delivery.message().get::<String>("name").is_some() {
+///         delivery.ack(&mut con).unwrap();
+///     } else {
+///         delivery.nack();
+///     }
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct Delivery {
+    key: String,
+    group: String,
+    consumer: String,
+    message: StreamId,
+    on_drop: DeliveryDropBehavior,
+    settled: bool,
+}
+
+impl Delivery {
+    pub fn new(key: &str, group: &str, consumer: &str, message: StreamId) -> Self {
+        Delivery {
+            key: key.to_string(),
+            group: group.to_string(),
+            consumer: consumer.to_string(),
+            message,
+            on_drop: DeliveryDropBehavior::Log,
+            settled: false,
+        }
+    }
+
+    /// Wrap every message of an `XREADGROUP` reply.
+    pub fn from_reply(reply: StreamReadReply, group: &str, consumer: &str) -> Vec<Delivery> {
+        let mut deliveries = vec![];
+        for key in reply.keys {
+            for message in key.ids {
+                deliveries.push(Delivery::new(&key.key, group, consumer, message));
+            }
+        }
+        deliveries
+    }
+
+    /// Set what happens when the delivery is dropped unsettled.
+    /// Defaults to `DeliveryDropBehavior::Log`.
+    pub fn on_drop(mut self, behavior: DeliveryDropBehavior) -> Self {
+        self.on_drop = behavior;
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+
+    pub fn id(&self) -> StreamEntryId {
+        self.message.id
+    }
+
+    pub fn message(&self) -> &StreamId {
+        &self.message
+    }
+
+    /// `XACK` the message. Returns false if it was no longer pending.
+    pub fn ack<C: ConnectionLike>(mut self, con: &mut C) -> RedisResult<bool> {
+        self.settled = true;
+        let acked: usize = con.xack(&self.key, &self.group, &[self.message.id])?;
+        Ok(acked > 0)
+    }
+
+    /// Give up on the message and leave it pending, to be
+    /// claimed again once it's idle (e.g. by a `StreamReaper`).
+    pub fn nack(mut self) {
+        self.settled = true;
+    }
+
+    /// Move the message to `dead_letter` now, regardless of its
+    /// delivery count. The dead-letter must be set up for the same
+    /// `key` and `group`. Returns false if it was no longer pending.
+    pub fn dead_letter<C: ConnectionLike>(
+        mut self,
+        con: &mut C,
+        dead_letter: &StreamDeadLetter,
+    ) -> RedisResult<bool> {
+        self.settled = true;
+        if !dead_letter.is_source(&self.key, &self.group) {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "dead-letter is for a different key or group",
+            )));
+        }
+        let options = StreamPendingOptions::default()
+            .range(self.message.id, self.message.id)
+            .count(1);
+        let reply: StreamPendingCountReply =
+            con.xpending_options(&self.key, &self.group, options)?;
+        match reply.ids.first() {
+            Some(pending) => dead_letter.route_pending(con, pending),
+            None => Ok(false),
+        }
+    }
+
+    /// Settle the delivery without touching Redis and take the message,
+    /// e.g. to hand it to a `StreamLease` or another task which acks it.
+    pub fn defer(mut self) -> StreamId {
+        self.settled = true;
+        std::mem::take(&mut self.message)
+    }
+}
+
+impl Drop for Delivery {
+    fn drop(&mut self) {
+        if self.settled || std::thread::panicking() {
+            return;
+        }
+        let warning = format!(
+            "unsettled delivery dropped: key={} group={} consumer={} id={}",
+            self.key, self.group, self.consumer, self.message.id
+        );
+        match self.on_drop {
+            DeliveryDropBehavior::Ignore => {}
+            DeliveryDropBehavior::Log => log::warn!("{}", warning),
+            DeliveryDropBehavior::Callback(callback) => callback(&warning),
+            DeliveryDropBehavior::Panic if cfg!(debug_assertions) => panic!("{}", warning),
+            DeliveryDropBehavior::Panic => log::warn!("{}", warning),
+        }
+    }
+}
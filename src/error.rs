@@ -0,0 +1,71 @@
+use redis::{RedisError, RedisResult};
+
+/// Stream-specific error replies, as classified by [`StreamErrorExt`].
+///
+/// [`StreamErrorExt`]: ./trait.StreamErrorExt.html
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamErrorKind {
+    /// `BUSYGROUP`: the consumer group already exists.
+    BusyGroup,
+    /// `NOGROUP`: the stream or consumer group doesn't exist.
+    NoGroup,
+    /// The id given to `XADD` or `XSETID` isn't greater than the
+    /// stream's top item (or `0-0`).
+    IdTooSmall,
+    /// The id isn't a valid stream id.
+    InvalidId,
+}
+
+/// Classifies the stream-specific errors returned by `StreamCommands`,
+/// so callers don't have to match on error messages.
+///
+/// ```no_run
+/// use redis_streams::{client_open,RedisResult,StreamCommands,StreamErrorExt,StreamErrorKind};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut con = client.get_connection().unwrap();
+///
+/// let result: RedisResult<String> = con.xadd("k1", "1000-0", &[("a", "b")]);
+/// match result {
+///     Err(ref err) if err.stream_error_kind() == Some(StreamErrorKind::IdTooSmall) => {
+///         // already added
+///     }
+///     other => { other.unwrap(); }
+/// }
+/// ```
+///
+pub trait StreamErrorExt {
+    /// Returns the kind of stream error, or `None` for any other error.
+    fn stream_error_kind(&self) -> Option<StreamErrorKind>;
+}
+
+impl StreamErrorExt for RedisError {
+    fn stream_error_kind(&self) -> Option<StreamErrorKind> {
+        match self.code() {
+            Some("BUSYGROUP") => Some(StreamErrorKind::BusyGroup),
+            Some("NOGROUP") => Some(StreamErrorKind::NoGroup),
+            Some("ERR") => {
+                let detail = self.detail().unwrap_or_default();
+                if detail.contains("smaller than the target stream top item")
+                    || detail.contains("must be greater than 0-0")
+                {
+                    Some(StreamErrorKind::IdTooSmall)
+                } else if detail.contains("Invalid stream ID") {
+                    Some(StreamErrorKind::InvalidId)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Turns a `BUSYGROUP` error into `Ok(false)` and success into `Ok(true)`.
+pub(crate) fn ignore_busy_group(result: RedisResult<()>) -> RedisResult<bool> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(ref err) if err.stream_error_kind() == Some(StreamErrorKind::BusyGroup) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,175 @@
+use crate::commands::StreamCommands;
+use crate::types::{
+    StreamAutoClaimOptions, StreamAutoClaimReply, StreamEntryId, StreamId, StreamRangeReply,
+};
+
+use redis::{ConnectionLike, RedisResult, ToRedisArgs};
+
+/// Iterator returned by [`xautoclaim_iter`].
+///
+/// Each item is one page of claimed messages. The iterator stops
+/// once Redis returns `0-0` as the next cursor or after the first error.
+///
+/// [`xautoclaim_iter`]: ./trait.StreamCommands.html#method.xautoclaim_iter
+///
+pub struct StreamAutoClaimIter<'a, C> {
+    con: &'a mut C,
+    key: Vec<Vec<u8>>,
+    group: Vec<Vec<u8>>,
+    consumer: Vec<Vec<u8>>,
+    min_idle_time: usize,
+    options: StreamAutoClaimOptions,
+    cursor: Option<StreamEntryId>,
+}
+
+impl<'a, C: ConnectionLike> StreamAutoClaimIter<'a, C> {
+    pub(crate) fn new<K: ToRedisArgs, G: ToRedisArgs, CN: ToRedisArgs>(
+        con: &'a mut C,
+        key: K,
+        group: G,
+        consumer: CN,
+        min_idle_time: usize,
+        options: StreamAutoClaimOptions,
+    ) -> Self {
+        StreamAutoClaimIter {
+            con,
+            key: key.to_redis_args(),
+            group: group.to_redis_args(),
+            consumer: consumer.to_redis_args(),
+            min_idle_time,
+            options,
+            cursor: Some(StreamEntryId::MIN),
+        }
+    }
+}
+
+impl<'a, C: ConnectionLike> Iterator for StreamAutoClaimIter<'a, C> {
+    type Item = RedisResult<StreamAutoClaimReply>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cursor = self.cursor.take()?;
+        let result = self.con.xautoclaim_options(
+            &self.key[..],
+            &self.group[..],
+            &self.consumer[..],
+            self.min_idle_time,
+            cursor,
+            self.options.clone(),
+        );
+        if let Ok(ref reply) = result {
+            if reply.next_stream_id != StreamEntryId::MIN {
+                self.cursor = Some(reply.next_stream_id);
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Iterator returned by [`xrange_page_iter`] and [`xrevrange_page_iter`].
+///
+/// Each item is one page of up to `count` messages. The next page starts
+/// right after the last message of the previous one, so no message is
+/// returned twice. The iterator stops at the end bound, on the first
+/// short or empty page, or after the first error.
+///
+/// [`xrange_page_iter`]: ./trait.StreamCommands.html#method.xrange_page_iter
+/// [`xrevrange_page_iter`]: ./trait.StreamCommands.html#method.xrevrange_page_iter
+///
+pub struct StreamRangePageIter<'a, C> {
+    con: &'a mut C,
+    key: Vec<Vec<u8>>,
+    cursor: Option<StreamEntryId>,
+    bound: StreamEntryId,
+    count: usize,
+    reverse: bool,
+}
+
+impl<'a, C: ConnectionLike> StreamRangePageIter<'a, C> {
+    pub(crate) fn new<K: ToRedisArgs>(
+        con: &'a mut C,
+        key: K,
+        start: StreamEntryId,
+        end: StreamEntryId,
+        count: usize,
+        reverse: bool,
+    ) -> Self {
+        let (cursor, bound) = if reverse { (end, start) } else { (start, end) };
+        StreamRangePageIter {
+            con,
+            key: key.to_redis_args(),
+            cursor: Some(cursor),
+            bound,
+            count: count.max(1),
+            reverse,
+        }
+    }
+}
+
+impl<'a, C: ConnectionLike> Iterator for StreamRangePageIter<'a, C> {
+    type Item = RedisResult<StreamRangeReply>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cursor = self.cursor.take()?;
+        let result: RedisResult<StreamRangeReply> = if self.reverse {
+            self.con
+                .xrevrange_count(&self.key[..], cursor, self.bound, self.count)
+        } else {
+            self.con
+                .xrange_count(&self.key[..], cursor, self.bound, self.count)
+        };
+        match result {
+            Ok(ref reply) if reply.ids.is_empty() => None,
+            Ok(reply) => {
+                if reply.ids.len() >= self.count {
+                    // Ranges are inclusive, so step past the last id.
+                    let last = reply.ids[reply.ids.len() - 1].id;
+                    self.cursor = if self.reverse {
+                        last.prev().filter(|id| *id >= self.bound)
+                    } else {
+                        last.next().filter(|id| *id <= self.bound)
+                    };
+                }
+                Some(Ok(reply))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Iterator returned by [`xrange_iter`] and [`xrevrange_iter`].
+///
+/// Yields the messages of a [`StreamRangePageIter`] one at a time.
+///
+/// [`xrange_iter`]: ./trait.StreamCommands.html#method.xrange_iter
+/// [`xrevrange_iter`]: ./trait.StreamCommands.html#method.xrevrange_iter
+/// [`StreamRangePageIter`]: ./struct.StreamRangePageIter.html
+///
+pub struct StreamRangeIter<'a, C> {
+    pages: StreamRangePageIter<'a, C>,
+    page: std::vec::IntoIter<StreamId>,
+}
+
+impl<'a, C: ConnectionLike> StreamRangeIter<'a, C> {
+    pub(crate) fn new(pages: StreamRangePageIter<'a, C>) -> Self {
+        StreamRangeIter {
+            pages,
+            page: vec![].into_iter(),
+        }
+    }
+}
+
+impl<'a, C: ConnectionLike> Iterator for StreamRangeIter<'a, C> {
+    type Item = RedisResult<StreamId>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(message) = self.page.next() {
+                return Some(Ok(message));
+            }
+            match self.pages.next()? {
+                Ok(reply) => self.page = reply.ids.into_iter(),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
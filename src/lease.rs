@@ -0,0 +1,220 @@
+use crate::script::lease_invocation;
+use crate::types::StreamEntryId;
+
+use redis::{ConnectionLike, ErrorKind, RedisError, RedisResult};
+
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Keeps in-flight messages claimed by a `consumer` while a slow handler runs.
+///
+/// Every `interval` a background thread re-claims the ids still pending for
+/// `consumer` with `XCLAIM ... JUSTID`, checking the owner and claiming in
+/// one script. This resets their idle time, so
+/// `xclaim`, `xautoclaim` or a `StreamReaper` with a longer `min_idle_time`
+/// leave them alone, and doesn't bump their delivery count.
+/// Ids no longer pending for `consumer` (e.g. claimed by another consumer)
+/// are dropped from the lease and returned by `lost`.
+///
+/// Heartbeating stops when the lease is acked, released or dropped,
+/// or after the first error, which is returned in the `StreamLeaseReport`.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamEntryId,StreamLease};
+/// use std::time::Duration;
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+///
+/// let ids: Vec<StreamEntryId> = vec!["1000-0".parse().unwrap()];
+/// let lease = StreamLease::start(
+///     client.get_connection().unwrap(),
+///     "k1",
+///     "g1",
+///     "c1",
+///     &ids,
+///     Duration::from_secs(10),
+/// );
+///
+/// // long running work...
+///
+/// let report = lease.ack().unwrap();
+/// if let Some(err) = report.heartbeat_error {
+///     println!("heartbeat failed: {}, lost: {:?}", err, report.lost);
+/// }
+/// ```
+///
+pub struct StreamLease<C> {
+    key: String,
+    group: String,
+    consumer: String,
+    ids: Arc<Mutex<Vec<StreamEntryId>>>,
+    lost: Arc<Mutex<Vec<StreamEntryId>>>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<(C, RedisResult<()>)>>,
+}
+
+/// Returned when a [`StreamLease`] is acked or released.
+///
+/// [`StreamLease`]: ./struct.StreamLease.html
+///
+#[derive(Debug)]
+pub struct StreamLeaseReport<C> {
+    /// The lease's connection.
+    pub con: C,
+    /// Ids acked by `ack`. Always empty for `release`.
+    pub acked: Vec<StreamEntryId>,
+    /// Ids which stopped being pending for `consumer` while leased.
+    pub lost: Vec<StreamEntryId>,
+    /// The error which stopped heartbeating early, if any.
+    pub heartbeat_error: Option<RedisError>,
+}
+
+impl<C> StreamLease<C>
+where
+    C: ConnectionLike + Send + 'static,
+{
+    /// Start heartbeating `ids` on a dedicated connection.
+    pub fn start(
+        con: C,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        ids: &[StreamEntryId],
+        interval: Duration,
+    ) -> Self {
+        let mut ids = ids.to_vec();
+        ids.sort();
+        ids.dedup();
+
+        let ids = Arc::new(Mutex::new(ids));
+        let lost = Arc::new(Mutex::new(vec![]));
+        let (stop, stopped) = channel();
+        let thread = {
+            let key = key.to_string();
+            let group = group.to_string();
+            let consumer = consumer.to_string();
+            let ids = ids.clone();
+            let lost = lost.clone();
+            let mut con = con;
+            thread::spawn(move || {
+                // Runs until the sender is used or dropped.
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    let result = heartbeat(&mut con, &key, &group, &consumer, &ids, &lost);
+                    if result.is_err() {
+                        return (con, result);
+                    }
+                }
+                (con, Ok(()))
+            })
+        };
+
+        StreamLease {
+            key: key.to_string(),
+            group: group.to_string(),
+            consumer: consumer.to_string(),
+            ids,
+            lost,
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Ids which stopped being pending for `consumer` while leased.
+    pub fn lost(&self) -> Vec<StreamEntryId> {
+        match self.lost.lock() {
+            Ok(lost) => lost.clone(),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Stop heartbeating and `XACK` the leased ids still pending for `consumer`.
+    ///
+    /// The ids are acked even if heartbeating failed, that error is
+    /// returned in the report. Returns an error if the `XACK` fails.
+    pub fn ack(mut self) -> RedisResult<StreamLeaseReport<C>> {
+        let mut report = self.stop()?;
+        let ids = self.held();
+        if !ids.is_empty() {
+            let acked: Vec<StreamEntryId> =
+                lease_invocation(&self.key, &self.group, &self.consumer, true, &ids)
+                    .invoke(&mut report.con)?;
+            // ids claimed by someone else since the last heartbeat
+            report
+                .lost
+                .extend(ids.into_iter().filter(|id| !acked.contains(id)));
+            report.acked = acked;
+        }
+        Ok(report)
+    }
+
+    /// Stop heartbeating without acking, leaving the ids
+    /// pending to be claimed once they're idle.
+    pub fn release(mut self) -> RedisResult<StreamLeaseReport<C>> {
+        self.stop()
+    }
+
+    fn held(&self) -> Vec<StreamEntryId> {
+        match self.ids.lock() {
+            Ok(ids) => ids.clone(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn stop(&mut self) -> RedisResult<StreamLeaseReport<C>> {
+        self.stop.take();
+        match self.thread.take().map(|thread| thread.join()) {
+            Some(Ok((con, result))) => Ok(StreamLeaseReport {
+                con,
+                acked: vec![],
+                lost: self.lost(),
+                heartbeat_error: result.err(),
+            }),
+            _ => Err(RedisError::from((
+                ErrorKind::ClientError,
+                "lease heartbeat thread panicked",
+            ))),
+        }
+    }
+}
+
+impl<C> Drop for StreamLease<C> {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn heartbeat<C: ConnectionLike>(
+    con: &mut C,
+    key: &str,
+    group: &str,
+    consumer: &str,
+    ids: &Mutex<Vec<StreamEntryId>>,
+    lost: &Mutex<Vec<StreamEntryId>>,
+) -> RedisResult<()> {
+    let leased = match ids.lock() {
+        Ok(ids) => ids.clone(),
+        Err(_) => return Ok(()),
+    };
+    if leased.is_empty() {
+        return Ok(());
+    }
+
+    // Only re-claim ids still pending for this consumer,
+    // so messages claimed by someone else aren't taken back.
+    let held: Vec<StreamEntryId> =
+        lease_invocation(key, group, consumer, false, &leased).invoke(con)?;
+    let gone: Vec<StreamEntryId> = leased.into_iter().filter(|id| !held.contains(id)).collect();
+    if !gone.is_empty() {
+        if let Ok(mut lost) = lost.lock() {
+            lost.extend(gone);
+        }
+    }
+    if let Ok(mut ids) = ids.lock() {
+        *ids = held;
+    }
+    Ok(())
+}
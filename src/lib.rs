@@ -19,6 +19,62 @@
 //! let mut con = client.get_connection().unwrap();
 //! ```
 //!
+//! For `redis::aio` connections, use the `AsyncStreamCommands` trait instead.
+//! It has the same methods and reply types as `StreamCommands`.
+//!
+//! ```no_run
+//! use redis_streams::{client_open,AsyncStreamCommands};
+//! # async fn run() -> redis::RedisResult<()> {
+//! let client = client_open("redis://127.0.0.1/0")?;
+//! let mut con = client.get_async_connection().await?;
+//! let len: usize = con.xlen("k1").await?;
+//! # Ok(()) }
+//! ```
+//!
+//! To continue `XREAD` calls across several keys, let a `StreamCursor` track
+//! the last id of each key. It can be saved to a Redis hash between runs.
+//! `StreamCheckpointReader` builds on it to resume non-group readers from
+//! their last processed ids.
+//!
+//! To consume streams as a `futures::Stream`, use `tail`. It follows the
+//! last seen id of each key and reconnects after connection errors.
+//!
+//! For high-throughput producers, `StreamProducer` batches messages per key
+//! into pipelined `XADD` calls. Its `StreamProducerSender` can be cloned
+//! across threads and its `StreamProducerSink` is a `futures::Sink`.
+//!
+//! Messages read by a consumer group can be wrapped in a `Delivery`, which
+//! has to be explicitly acked, nacked, dead-lettered or deferred.
+//!
+//! Stream-specific error replies like `BUSYGROUP` and `NOGROUP` can be
+//! told apart with `StreamErrorExt::stream_error_kind`.
+//!
+//! To batch commands into one round trip, queue them on a `redis::Pipeline`
+//! with the `StreamPipelineCommands` trait and decode the crate's reply types
+//! from the result.
+//!
+//! Poison messages which keep failing can be moved to a dead-letter stream
+//! with `StreamDeadLetter` and redriven once they can be processed.
+//! Messages left pending by dead consumers can be reclaimed with `StreamReaper`.
+//! Handlers which run longer than the reaper's idle time can hold on to
+//! their messages with a `StreamLease`.
+//!
+//! With the `serde` feature enabled, messages can be added from any `Serialize`
+//! struct with `xadd_serialize` and decoded with `StreamId::deserialize`.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Event { name: String, count: u32 }
+//!
+//! let _: String = con.xadd_serialize("k1", "*", &Event { name: "signup".into(), count: 2 })?;
+//! let reply: StreamRangeReply = con.xrange_all("k1")?;
+//! let events: StreamTypedRangeReply<Event> = reply.deserialize()?;
+//! ```
+//!
+//! With the `derive` feature enabled, `#[derive(StreamMessage)]` maps structs
+//! onto stream fields for `xadd_message` and `StreamId::to_message`.
+//! See the `StreamMessage` trait for the supported attributes.
+//!
 //! This crate also exposes all top-level `redis-rs` types.
 //! To pick up all `redis-rs` Commands, just use the `Commands` trait.
 //!
@@ -34,30 +90,102 @@ pub use redis::{
     Value,
 };
 
-pub use crate::commands::StreamCommands;
+pub use crate::checkpoint::{StreamCheckpointReader, StreamCheckpointStore};
+
+pub use crate::commands::{AsyncStreamCommands, StreamCommands, StreamPipelineCommands};
+
+pub use crate::consumer::{StreamConsumer, StreamConsumerShutdown};
+
+pub use crate::cursor::StreamCursor;
+
+pub use crate::dead_letter::StreamDeadLetter;
+
+pub use crate::delivery::{Delivery, DeliveryDropBehavior};
+
+pub use crate::error::{StreamErrorExt, StreamErrorKind};
+
+pub use crate::iter::{StreamAutoClaimIter, StreamRangeIter, StreamRangePageIter};
+
+pub use crate::lease::{StreamLease, StreamLeaseReport};
+
+pub use crate::message::StreamMessage;
+
+pub use crate::producer::{
+    StreamProducer, StreamProducerHandle, StreamProducerMessage, StreamProducerOptions,
+    StreamProducerSender, StreamProducerSink,
+};
+
+pub use crate::reaper::{StreamReaper, StreamReaperReport};
+
+pub use crate::tail::tail;
+
+#[doc(hidden)]
+pub use crate::message::__private;
+
+#[cfg(feature = "derive")]
+pub use redis_streams_derive::StreamMessage;
+
+#[cfg(feature = "serde")]
+pub use crate::serialize::{
+    to_stream_fields, StreamTypedId, StreamTypedKey, StreamTypedRangeReply, StreamTypedReadReply,
+};
 
 pub use crate::types::{
     // stream types
+    StreamAddAckOptions,
+    StreamAddOptions,
+    StreamAutoClaimOptions,
+    StreamAutoClaimReply,
+    StreamBytesId,
+    StreamBytesKey,
+    StreamBytesRangeReply,
+    StreamBytesReadReply,
     StreamClaimOptions,
     StreamClaimReply,
+    StreamEntryId,
+    StreamGroupCreateOptions,
     StreamId,
+    StreamIdSpec,
     StreamInfoConsumer,
+    StreamInfoConsumerFull,
     StreamInfoConsumersReply,
     StreamInfoGroup,
+    StreamInfoGroupFull,
     StreamInfoGroupsReply,
+    StreamInfoPendingEntry,
+    StreamInfoStreamFullReply,
     StreamInfoStreamReply,
     StreamKey,
     StreamMaxlen,
     StreamPendingCountReply,
     StreamPendingData,
     StreamPendingId,
+    StreamPendingOptions,
     StreamPendingReply,
     StreamRangeReply,
     StreamReadOptions,
     StreamReadReply,
+    StreamSetIdOptions,
+    StreamTrimOptions,
+    StreamTrimStrategy,
 };
 
+mod checkpoint;
 mod commands;
+mod consumer;
+mod cursor;
+mod dead_letter;
+mod delivery;
+mod error;
+mod iter;
+mod lease;
+mod message;
+mod producer;
+mod reaper;
+mod script;
+#[cfg(feature = "serde")]
+mod serialize;
+mod tail;
 mod types;
 
 /// Curry `redis::Client::open` calls.
@@ -0,0 +1,171 @@
+use crate::types::StreamId;
+
+use redis::{from_redis_value, ErrorKind, FromRedisValue, RedisError, RedisResult, ToRedisArgs};
+
+/// Mapping between a Rust type and stream field/value pairs.
+///
+/// Usually implemented with `#[derive(StreamMessage)]` (requires the
+/// `derive` feature), which supports these field attributes:
+///
+/// - `#[stream(rename = "name")]` use `name` as the stream field.
+/// - `#[stream(skip)]` never write the field and use `Default` when reading.
+/// - `#[stream(default)]` use `Default` when the field is missing.
+/// - `#[stream(json)]` store the field as JSON (requires the `json` feature).
+///
+/// ```ignore
+/// #[derive(StreamMessage)]
+/// struct Event {
+///     #[stream(rename = "type")]
+///     kind: String,
+///     count: u32,
+///     note: Option<String>,
+///     #[stream(default)]
+///     retries: u8,
+/// }
+///
+/// let _: String = con.xadd_message("k1", "*", &event)?;
+/// let reply: StreamRangeReply = con.xrange_all("k1")?;
+/// let event = Event::from_stream_id(&reply.ids[0])?;
+/// ```
+///
+/// Plain fields are encoded with `ToRedisArgs` and decoded with
+/// `FromRedisValue`, except `bool` fields, which are stored as `1`/`0`. Fields declared as `Option<_>` are skipped when
+/// `None` and decode as `None` when missing. Any other missing field
+/// is an error unless it's marked `default` or `skip`.
+///
+pub trait StreamMessage: Sized {
+    /// Encode this message as field/value pairs for `XADD`.
+    fn to_stream_fields(&self) -> RedisResult<Vec<(String, Vec<u8>)>>;
+
+    /// Decode this message from a `StreamId`.
+    fn from_stream_id(stream_id: &StreamId) -> RedisResult<Self>;
+}
+
+impl StreamId {
+    /// Decode this message into a `StreamMessage` type.
+    ///
+    pub fn to_message<T: StreamMessage>(&self) -> RedisResult<T> {
+        T::from_stream_id(self)
+    }
+}
+
+/// Helpers called from `#[derive(StreamMessage)]` code. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    fn field_error(desc: &'static str, stream_id: &StreamId, field: &str) -> RedisError {
+        RedisError::from((
+            ErrorKind::TypeError,
+            desc,
+            format!("id {}: field `{}`", stream_id.id, field),
+        ))
+    }
+
+    pub fn encode_field<T: ToRedisArgs>(
+        fields: &mut Vec<(String, Vec<u8>)>,
+        field: &str,
+        value: &T,
+    ) -> RedisResult<()> {
+        let mut args = value.to_redis_args();
+        match args.len() {
+            0 => Ok(()),
+            1 => {
+                fields.push((field.to_string(), args.remove(0)));
+                Ok(())
+            }
+            _ => Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Stream message field encodes to multiple values",
+                format!("field `{}`", field),
+            ))),
+        }
+    }
+
+    pub fn decode_field<T: FromRedisValue>(stream_id: &StreamId, field: &str) -> RedisResult<T> {
+        match stream_id.find(&field) {
+            Some(value) => from_redis_value(value)
+                .map_err(|_| field_error("Invalid stream message field", stream_id, field)),
+            None => Err(field_error(
+                "Missing stream message field",
+                stream_id,
+                field,
+            )),
+        }
+    }
+
+    pub fn decode_optional_field<T: FromRedisValue>(
+        stream_id: &StreamId,
+        field: &str,
+    ) -> RedisResult<Option<T>> {
+        match stream_id.find(&field) {
+            Some(_) => decode_field(stream_id, field).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn decode_field_or_default<T: FromRedisValue + Default>(
+        stream_id: &StreamId,
+        field: &str,
+    ) -> RedisResult<T> {
+        match stream_id.find(&field) {
+            Some(_) => decode_field(stream_id, field),
+            None => Ok(T::default()),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn encode_json_field<T: serde::Serialize + ?Sized>(
+        fields: &mut Vec<(String, Vec<u8>)>,
+        field: &str,
+        value: &T,
+    ) -> RedisResult<()> {
+        let json = serde_json::to_vec(value).map_err(|err| {
+            RedisError::from((
+                ErrorKind::TypeError,
+                "Failed to encode stream message field as json",
+                format!("field `{}`: {}", field, err),
+            ))
+        })?;
+        fields.push((field.to_string(), json));
+        Ok(())
+    }
+
+    #[cfg(feature = "json")]
+    pub fn decode_json_field<T: serde::de::DeserializeOwned>(
+        stream_id: &StreamId,
+        field: &str,
+    ) -> RedisResult<T> {
+        let bytes: Vec<u8> = decode_field(stream_id, field)?;
+        serde_json::from_slice(&bytes).map_err(|err| {
+            RedisError::from((
+                ErrorKind::TypeError,
+                "Invalid json in stream message field",
+                format!("id {}: field `{}`: {}", stream_id.id, field, err),
+            ))
+        })
+    }
+
+    // `None` is written as json `null`, so a present field decodes as `Option<T>`
+    #[cfg(feature = "json")]
+    pub fn decode_json_optional_field<T: serde::de::DeserializeOwned>(
+        stream_id: &StreamId,
+        field: &str,
+    ) -> RedisResult<Option<T>> {
+        match stream_id.find(&field) {
+            Some(_) => decode_json_field(stream_id, field),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn decode_json_field_or_default<T: serde::de::DeserializeOwned + Default>(
+        stream_id: &StreamId,
+        field: &str,
+    ) -> RedisResult<T> {
+        match stream_id.find(&field) {
+            Some(_) => decode_json_field(stream_id, field),
+            None => Ok(T::default()),
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use crate::script::add_batch_invocation;
+use crate::types::{StreamEntryId, StreamMaxlen};
+
+use futures::channel::oneshot;
+use futures::Sink;
+use redis::{
+    from_redis_value, ConnectionLike, ErrorKind, RedisError, RedisResult, ToRedisArgs, Value,
+};
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Batching options for [`StreamProducer`].
+///
+/// [`StreamProducer`]: ./struct.StreamProducer.html
+///
+#[derive(Debug, Clone)]
+pub struct StreamProducerOptions {
+    batch_size: usize,
+    linger: Duration,
+    max_in_flight_bytes: usize,
+    maxlen: Option<StreamMaxlen>,
+}
+
+impl Default for StreamProducerOptions {
+    fn default() -> Self {
+        StreamProducerOptions {
+            batch_size: 100,
+            linger: Duration::from_millis(5),
+            max_in_flight_bytes: 1024 * 1024,
+            maxlen: None,
+        }
+    }
+}
+
+impl StreamProducerOptions {
+    /// Write a key's messages once `n` of them are buffered. Defaults to 100.
+    pub fn batch_size(mut self, n: usize) -> Self {
+        self.batch_size = n.max(1);
+        self
+    }
+
+    /// Write a key's messages once the first of them is `linger` old.
+    /// Defaults to 5ms.
+    pub fn linger(mut self, linger: Duration) -> Self {
+        self.linger = linger;
+        self
+    }
+
+    /// Block senders while this many bytes of field names and values
+    /// are buffered or being written. Defaults to 1MiB.
+    pub fn max_in_flight_bytes(mut self, n: usize) -> Self {
+        self.max_in_flight_bytes = n;
+        self
+    }
+
+    /// Add messages with `xadd_maxlen` instead of `xadd`.
+    pub fn maxlen(mut self, maxlen: StreamMaxlen) -> Self {
+        self.maxlen = Some(maxlen);
+        self
+    }
+}
+
+/// A message for [`StreamProducerSink`], created with a handle which
+/// resolves to its `id` once it's written.
+///
+/// [`StreamProducerSink`]: ./struct.StreamProducerSink.html
+///
+pub struct StreamProducerMessage {
+    key: String,
+    args: Vec<Vec<u8>>,
+    done: oneshot::Sender<RedisResult<StreamEntryId>>,
+}
+
+impl StreamProducerMessage {
+    pub fn new<F: ToRedisArgs, V: ToRedisArgs>(
+        key: &str,
+        items: &[(F, V)],
+    ) -> (Self, StreamProducerHandle) {
+        let (done, id) = oneshot::channel();
+        let message = StreamProducerMessage {
+            key: key.to_string(),
+            args: items.to_redis_args(),
+            done,
+        };
+        (message, StreamProducerHandle { id })
+    }
+
+    fn size(&self) -> usize {
+        self.key.len() + self.args.iter().map(|arg| arg.len()).sum::<usize>()
+    }
+}
+
+/// Completion handle for a produced message.
+///
+/// Await it or call `wait` to get the `id` Redis assigned, or the error
+/// which failed the message's batch.
+///
+pub struct StreamProducerHandle {
+    id: oneshot::Receiver<RedisResult<StreamEntryId>>,
+}
+
+impl StreamProducerHandle {
+    /// Block until the message is written.
+    pub fn wait(self) -> RedisResult<StreamEntryId> {
+        futures::executor::block_on(self)
+    }
+}
+
+impl Future for StreamProducerHandle {
+    type Output = RedisResult<StreamEntryId>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.id).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(stopped())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+enum Command {
+    Message(StreamProducerMessage),
+    Flush(oneshot::Sender<()>),
+    Close,
+}
+
+#[derive(Default)]
+struct Budget {
+    bytes: usize,
+    closed: bool,
+    wakers: Vec<Waker>,
+}
+
+struct Shared {
+    budget: Mutex<Budget>,
+    available: Condvar,
+    max_bytes: usize,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Budget> {
+        self.budget.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn release(&self, bytes: usize) {
+        let mut budget = self.lock();
+        budget.bytes -= bytes;
+        for waker in budget.wakers.drain(..) {
+            waker.wake();
+        }
+        self.available.notify_all();
+    }
+
+    // Wakes up senders blocked on a producer which won't release their bytes.
+    fn close(&self) {
+        let mut budget = self.lock();
+        budget.closed = true;
+        for waker in budget.wakers.drain(..) {
+            waker.wake();
+        }
+        self.available.notify_all();
+    }
+}
+
+/// Batches messages from many callers into one `XADD` script call per key.
+///
+/// Messages are handed to a background thread which owns the connection
+/// and buffers them per key. A key's messages are written once
+/// `batch_size` of them are buffered, the first of them is `linger` old,
+/// or `flush` is called. Messages for the same key keep their order. Once
+/// `max_in_flight_bytes` are buffered or being written, `xadd` blocks
+/// until a batch completes.
+///
+/// Use `sender` to get cloneable handles for other threads or tasks,
+/// and `into_sink` for a `futures::Sink`.
+///
+/// A failing `XADD` only fails its own handle. If the call for a key fails
+/// as a whole, e.g. because the connection dropped, every handle of that
+/// key gets the error and its messages may or may not have been added.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamProducer,StreamProducerOptions};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+///
+/// let producer = StreamProducer::start(
+///     client.get_connection().unwrap(),
+///     StreamProducerOptions::default().batch_size(500),
+/// );
+///
+/// let sender = producer.sender();
+/// let worker = std::thread::spawn(move || {
+///     sender.xadd("k2", &[("name", "worker")]).unwrap().wait().unwrap();
+/// });
+///
+/// let handles: Vec<_> = (0..1000)
+///     .map(|i| producer.xadd("k1", &[("idx", i)]).unwrap())
+///     .collect();
+/// for handle in handles {
+///     println!("added {}", handle.wait().unwrap());
+/// }
+/// worker.join().unwrap();
+/// let con = producer.close().unwrap();
+/// ```
+///
+pub struct StreamProducer<C> {
+    sender: StreamProducerSender,
+    thread: Option<JoinHandle<C>>,
+}
+
+impl<C> StreamProducer<C>
+where
+    C: ConnectionLike + Send + 'static,
+{
+    pub fn start(con: C, options: StreamProducerOptions) -> Self {
+        let shared = Arc::new(Shared {
+            budget: Mutex::new(Budget::default()),
+            available: Condvar::new(),
+            max_bytes: options.max_in_flight_bytes,
+        });
+        let (commands, received) = channel();
+        let thread = {
+            let shared = shared.clone();
+            let mut con = con;
+            thread::spawn(move || {
+                run(&mut con, received, &shared, &options);
+                shared.close();
+                con
+            })
+        };
+        StreamProducer {
+            sender: StreamProducerSender { commands, shared },
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns a cloneable handle for queueing messages from other threads.
+    pub fn sender(&self) -> StreamProducerSender {
+        self.sender.clone()
+    }
+
+    /// Queue a message for `key`, blocking while too many bytes are in flight.
+    pub fn xadd<F: ToRedisArgs, V: ToRedisArgs>(
+        &self,
+        key: &str,
+        items: &[(F, V)],
+    ) -> RedisResult<StreamProducerHandle> {
+        self.sender.xadd(key, items)
+    }
+
+    /// Queue a message, blocking while too many bytes are in flight.
+    pub fn send(&self, message: StreamProducerMessage) -> RedisResult<()> {
+        self.sender.send(message)
+    }
+
+    /// Write the buffered messages and block until they're done.
+    pub fn flush(&self) -> RedisResult<()> {
+        self.sender.flush()
+    }
+
+    /// Write the buffered messages, stop the background thread and
+    /// return the connection. Senders fail once it's stopped.
+    pub fn close(mut self) -> RedisResult<C> {
+        let _ = self.sender.commands.send(Command::Close);
+        match self.thread.take().map(|thread| thread.join()) {
+            Some(Ok(con)) => Ok(con),
+            _ => Err(stopped()),
+        }
+    }
+
+    pub fn into_sink(self) -> StreamProducerSink<C> {
+        StreamProducerSink {
+            producer: self,
+            flushing: None,
+        }
+    }
+}
+
+impl<C> Drop for StreamProducer<C> {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.sender.commands.send(Command::Close);
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Cloneable handle for queueing messages on a [`StreamProducer`],
+/// returned by `sender`.
+///
+/// Every method fails once the producer is closed or dropped.
+///
+/// [`StreamProducer`]: ./struct.StreamProducer.html
+///
+#[derive(Clone)]
+pub struct StreamProducerSender {
+    commands: Sender<Command>,
+    shared: Arc<Shared>,
+}
+
+impl StreamProducerSender {
+    /// Queue a message for `key`, blocking while too many bytes are in flight.
+    pub fn xadd<F: ToRedisArgs, V: ToRedisArgs>(
+        &self,
+        key: &str,
+        items: &[(F, V)],
+    ) -> RedisResult<StreamProducerHandle> {
+        let (message, handle) = StreamProducerMessage::new(key, items);
+        self.send(message)?;
+        Ok(handle)
+    }
+
+    /// Queue a message, blocking while too many bytes are in flight.
+    pub fn send(&self, message: StreamProducerMessage) -> RedisResult<()> {
+        {
+            let mut budget = self.shared.lock();
+            while budget.bytes >= self.shared.max_bytes && !budget.closed {
+                budget = self
+                    .shared
+                    .available
+                    .wait(budget)
+                    .unwrap_or_else(|err| err.into_inner());
+            }
+        }
+        self.start_send(message)
+    }
+
+    /// Write the buffered messages and block until they're done.
+    pub fn flush(&self) -> RedisResult<()> {
+        futures::executor::block_on(self.start_flush()?).map_err(|_| stopped())
+    }
+
+    fn start_send(&self, message: StreamProducerMessage) -> RedisResult<()> {
+        let size = message.size();
+        {
+            let mut budget = self.shared.lock();
+            if budget.closed {
+                return Err(stopped());
+            }
+            budget.bytes += size;
+        }
+        if self.commands.send(Command::Message(message)).is_err() {
+            self.shared.release(size);
+            return Err(stopped());
+        }
+        Ok(())
+    }
+
+    fn start_flush(&self) -> RedisResult<oneshot::Receiver<()>> {
+        let (done, flushed) = oneshot::channel();
+        match self.commands.send(Command::Flush(done)) {
+            Ok(()) => Ok(flushed),
+            Err(_) => Err(stopped()),
+        }
+    }
+}
+
+/// `futures::Sink` over a [`StreamProducer`], returned by `into_sink`.
+///
+/// `poll_ready` waits while too many bytes are in flight and
+/// `poll_flush` waits until every sent message is written.
+///
+/// ```no_run
+/// use futures::SinkExt;
+/// use redis_streams::{client_open,StreamProducer,StreamProducerMessage,StreamProducerOptions};
+/// # async fn run() {
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut sink = StreamProducer::start(
+///     client.get_connection().unwrap(),
+///     StreamProducerOptions::default(),
+/// )
+/// .into_sink();
+///
+/// let (message, handle) = StreamProducerMessage::new("k1", &[("name", "signup")]);
+/// sink.send(message).await.unwrap();
+/// println!("added {}", handle.await.unwrap());
+/// # }
+/// ```
+///
+/// [`StreamProducer`]: ./struct.StreamProducer.html
+///
+pub struct StreamProducerSink<C> {
+    producer: StreamProducer<C>,
+    flushing: Option<oneshot::Receiver<()>>,
+}
+
+impl<C> StreamProducerSink<C>
+where
+    C: ConnectionLike + Send + 'static,
+{
+    pub fn into_inner(self) -> StreamProducer<C> {
+        self.producer
+    }
+}
+
+impl<C> Sink<StreamProducerMessage> for StreamProducerSink<C>
+where
+    C: ConnectionLike + Send + 'static,
+{
+    type Error = RedisError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<RedisResult<()>> {
+        let shared = &self.producer.sender.shared;
+        let mut budget = shared.lock();
+        if budget.closed {
+            Poll::Ready(Err(stopped()))
+        } else if budget.bytes < shared.max_bytes {
+            Poll::Ready(Ok(()))
+        } else {
+            budget.wakers.push(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, message: StreamProducerMessage) -> RedisResult<()> {
+        self.producer.sender.start_send(message)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<RedisResult<()>> {
+        if self.flushing.is_none() {
+            let flushed = self.producer.sender.start_flush()?;
+            self.flushing = Some(flushed);
+        }
+        let result = match self.flushing {
+            Some(ref mut flushed) => match Pin::new(flushed).poll(cx) {
+                Poll::Ready(result) => result.map_err(|_| stopped()),
+                Poll::Pending => return Poll::Pending,
+            },
+            None => Ok(()),
+        };
+        self.flushing = None;
+        Poll::Ready(result)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<RedisResult<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+fn stopped() -> RedisError {
+    RedisError::from((ErrorKind::ClientError, "stream producer stopped"))
+}
+
+// The messages buffered for one key.
+struct Buffer {
+    messages: Vec<StreamProducerMessage>,
+    deadline: Instant,
+}
+
+fn run<C: ConnectionLike>(
+    con: &mut C,
+    commands: Receiver<Command>,
+    shared: &Shared,
+    options: &StreamProducerOptions,
+) {
+    let mut buffers: HashMap<String, Buffer> = HashMap::new();
+    loop {
+        let received = match buffers.values().map(|buffer| buffer.deadline).min() {
+            Some(deadline) => {
+                commands.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => commands.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(Command::Message(message)) => {
+                let key = message.key.clone();
+                let buffer = buffers.entry(key.clone()).or_insert_with(|| Buffer {
+                    messages: vec![],
+                    deadline: Instant::now() + options.linger,
+                });
+                buffer.messages.push(message);
+                if buffer.messages.len() >= options.batch_size {
+                    let full = take_buffers(&mut buffers, |k, _| *k == key);
+                    write_buffers(con, full, shared, options);
+                }
+            }
+            Ok(Command::Flush(done)) => {
+                let all = take_buffers(&mut buffers, |_, _| true);
+                write_buffers(con, all, shared, options);
+                let _ = done.send(());
+            }
+            Ok(Command::Close) | Err(RecvTimeoutError::Disconnected) => {
+                let all = take_buffers(&mut buffers, |_, _| true);
+                write_buffers(con, all, shared, options);
+                return;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+
+        // Keys can become due while messages keep arriving.
+        let now = Instant::now();
+        let due = take_buffers(&mut buffers, |_, buffer| buffer.deadline <= now);
+        write_buffers(con, due, shared, options);
+    }
+}
+
+// Remove the matching buffers and return them with their keys.
+fn take_buffers<F: Fn(&String, &Buffer) -> bool>(
+    buffers: &mut HashMap<String, Buffer>,
+    matches: F,
+) -> Vec<(String, Buffer)> {
+    let keys: Vec<String> = buffers
+        .iter()
+        .filter(|(key, buffer)| matches(key, buffer))
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| buffers.remove(&key).map(|buffer| (key, buffer)))
+        .collect()
+}
+
+fn write_buffers<C: ConnectionLike>(
+    con: &mut C,
+    buffers: Vec<(String, Buffer)>,
+    shared: &Shared,
+    options: &StreamProducerOptions,
+) {
+    for (key, buffer) in buffers {
+        write_batch(con, &key, buffer.messages, shared, options);
+    }
+}
+
+fn write_batch<C: ConnectionLike>(
+    con: &mut C,
+    key: &str,
+    batch: Vec<StreamProducerMessage>,
+    shared: &Shared,
+    options: &StreamProducerOptions,
+) {
+    let size = batch.iter().map(|message| message.size()).sum();
+    let count = batch.len();
+    let xadd_options = options.maxlen.to_redis_args();
+    let result = {
+        let args: Vec<&[Vec<u8>]> = batch.iter().map(|message| &message.args[..]).collect();
+        add_batch_invocation(key, &xadd_options, &args).invoke::<Vec<Value>>(con)
+    };
+
+    match result {
+        Ok(ids) if ids.len() == count => {
+            for (message, id) in batch.into_iter().zip(ids) {
+                let _ = message.done.send(added_id(&id));
+            }
+        }
+        Ok(ids) => {
+            for message in batch {
+                let err = RedisError::from((
+                    ErrorKind::TypeError,
+                    "stream producer batch failed",
+                    format!("expected {} ids but got {}", count, ids.len()),
+                ));
+                let _ = message.done.send(Err(err));
+            }
+        }
+        Err(err) => {
+            // RedisError isn't Clone, so each handle gets a copy of the message.
+            let detail = err.to_string();
+            for message in batch {
+                let err = (err.kind(), "stream producer batch failed", detail.clone());
+                let _ = message.done.send(Err(RedisError::from(err)));
+            }
+        }
+    }
+    shared.release(size);
+}
+
+// An id, or the error of a failed `XADD` wrapped in an array.
+fn added_id(value: &Value) -> RedisResult<StreamEntryId> {
+    match *value {
+        Value::Bulk(ref err) => {
+            let detail: String = match err.first() {
+                Some(detail) => from_redis_value(detail)?,
+                None => String::new(),
+            };
+            Err(RedisError::from((
+                ErrorKind::ResponseError,
+                "stream producer XADD failed",
+                detail,
+            )))
+        }
+        _ => from_redis_value(value),
+    }
+}
@@ -0,0 +1,187 @@
+use crate::commands::StreamCommands;
+use crate::consumer::StreamConsumerShutdown;
+use crate::types::{
+    StreamClaimReply, StreamEntryId, StreamId, StreamInfoConsumersReply, StreamPendingCountReply,
+    StreamPendingOptions,
+};
+
+use redis::{ConnectionLike, RedisResult};
+
+use std::thread::sleep;
+use std::time::Duration;
+
+/// What a single [`reap`] call did.
+///
+/// [`reap`]: ./struct.StreamReaper.html#method.reap
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamReaperReport {
+    /// Messages claimed for the reaper's `consumer`, with their fields.
+    pub claimed: Vec<StreamId>,
+    /// Consumers messages were claimed from.
+    pub reaped_consumers: Vec<String>,
+    /// Consumers deleted from the `group`.
+    pub deleted_consumers: Vec<String>,
+}
+
+impl StreamReaperReport {
+    pub fn is_empty(&self) -> bool {
+        self.claimed.is_empty() && self.deleted_consumers.is_empty()
+    }
+}
+
+/// Reclaims pending messages from dead consumers of a `group`.
+///
+/// Consumers which haven't interacted with the stream for `min_idle_time`
+/// milliseconds (per `XINFO CONSUMERS`) are treated as dead. Their pending
+/// messages idle for at least as long are claimed for the reaper's own
+/// `consumer` and returned in the report, so run the reaper from a live
+/// worker which processes and acks them.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamReaper};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut con = client.get_connection().unwrap();
+///
+/// // Claim messages from consumers idle for 5 minutes and delete
+/// // consumers idle for an hour once nothing is pending for them.
+/// let reaper = StreamReaper::new("k1", "g1", "c1", 300_000).delete_idle_consumers(3_600_000);
+///
+/// let report = reaper.reap(&mut con).unwrap();
+/// for msg in report.claimed {
+///     println!("reclaimed {}", msg.id);
+/// }
+/// ```
+///
+pub struct StreamReaper {
+    key: String,
+    group: String,
+    consumer: String,
+    min_idle_time: usize,
+    count: usize,
+    delete_idle_time: Option<usize>,
+    shutdown: StreamConsumerShutdown,
+}
+
+impl StreamReaper {
+    pub fn new(key: &str, group: &str, consumer: &str, min_idle_time: usize) -> Self {
+        StreamReaper {
+            key: key.to_string(),
+            group: group.to_string(),
+            consumer: consumer.to_string(),
+            min_idle_time,
+            count: 100,
+            delete_idle_time: None,
+            shutdown: StreamConsumerShutdown::default(),
+        }
+    }
+
+    /// Set how many messages are claimed per `XCLAIM` call. Defaults to 100.
+    pub fn count(mut self, n: usize) -> Self {
+        self.count = n;
+        self
+    }
+
+    /// Delete consumers idle for at least `ms` milliseconds
+    /// which have no pending messages left.
+    pub fn delete_idle_consumers(mut self, ms: usize) -> Self {
+        self.delete_idle_time = Some(ms);
+        self
+    }
+
+    /// Returns a handle which stops `run` from another thread.
+    pub fn shutdown_handle(&self) -> StreamConsumerShutdown {
+        self.shutdown.clone()
+    }
+
+    /// Claim the pending messages of every dead consumer once and
+    /// delete idle consumers if enabled.
+    pub fn reap<C: ConnectionLike>(&self, con: &mut C) -> RedisResult<StreamReaperReport> {
+        let mut report = StreamReaperReport::default();
+        let reply: StreamInfoConsumersReply = con.xinfo_consumers(&self.key, &self.group)?;
+        for info in &reply.consumers {
+            if info.name == self.consumer {
+                continue;
+            }
+            if info.pending > 0 && info.idle >= self.min_idle_time {
+                let claimed = self.claim_from(con, &info.name)?;
+                if !claimed.is_empty() {
+                    report.reaped_consumers.push(info.name.clone());
+                    report.claimed.extend(claimed);
+                }
+            }
+            let idle_enough = matches!(self.delete_idle_time, Some(ms) if info.idle >= ms);
+            if idle_enough && self.delete_consumer(con, &info.name)? {
+                report.deleted_consumers.push(info.name.clone());
+            }
+        }
+        Ok(report)
+    }
+
+    /// Call `reap` every `interval` until shutdown is signaled,
+    /// passing each non-empty report to `handler`.
+    pub fn run<C, H>(&self, con: &mut C, interval: Duration, mut handler: H) -> RedisResult<()>
+    where
+        C: ConnectionLike,
+        H: FnMut(StreamReaperReport),
+    {
+        while !self.shutdown.is_shutdown() {
+            let report = self.reap(con)?;
+            if !report.is_empty() {
+                handler(report);
+            }
+            if !self.shutdown.is_shutdown() {
+                sleep(interval);
+            }
+        }
+        Ok(())
+    }
+
+    // Pages through the consumer's pending entries by id, so entries
+    // which can't be claimed (e.g. they were deleted) are only seen once.
+    fn claim_from<C: ConnectionLike>(
+        &self,
+        con: &mut C,
+        consumer: &str,
+    ) -> RedisResult<Vec<StreamId>> {
+        let mut claimed = vec![];
+        let mut start = StreamEntryId::MIN;
+        loop {
+            let options = StreamPendingOptions::default()
+                .range(start, "+")
+                .count(self.count)
+                .consumer(consumer)
+                .idle(self.min_idle_time);
+            let pending: StreamPendingCountReply =
+                con.xpending_options(&self.key, &self.group, options)?;
+            let ids: Vec<StreamEntryId> = pending.ids.iter().map(|p| p.id).collect();
+            if !ids.is_empty() {
+                let reply: StreamClaimReply = con.xclaim(
+                    &self.key,
+                    &self.group,
+                    &self.consumer,
+                    self.min_idle_time,
+                    &ids,
+                )?;
+                claimed.extend(reply.ids);
+            }
+            match ids.last().and_then(|id| id.next()) {
+                Some(next) if ids.len() >= self.count => start = next,
+                _ => return Ok(claimed),
+            }
+        }
+    }
+
+    // XGROUP DELCONSUMER drops the consumer's pending messages,
+    // so only delete it when nothing is pending.
+    fn delete_consumer<C: ConnectionLike>(&self, con: &mut C, consumer: &str) -> RedisResult<bool> {
+        let options = StreamPendingOptions::default().count(1).consumer(consumer);
+        let pending: StreamPendingCountReply =
+            con.xpending_options(&self.key, &self.group, options)?;
+        if !pending.ids.is_empty() {
+            return Ok(false);
+        }
+        let _: usize = con.xgroup_delconsumer(&self.key, &self.group, consumer)?;
+        Ok(true)
+    }
+}
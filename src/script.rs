@@ -0,0 +1,157 @@
+use crate::types::{StreamAddAckOptions, StreamEntryId};
+
+use lazy_static::lazy_static;
+
+use redis::{Script, ScriptInvocation, ToRedisArgs};
+
+// KEYS[1] destination stream, KEYS[2] source stream
+// ARGV[1] group, ARGV[2] source id, ARGV[3] delete flag,
+// ARGV[4..] XADD args (options, id and field/values)
+//
+// Everything that can fail runs before the first write, so the
+// script either does all of its writes or none of them.
+const ADD_ACK: &str = r"
+redis.replicate_commands()
+local pending = redis.call('XPENDING', KEYS[2], ARGV[1], ARGV[2], ARGV[2], 1)
+if #pending == 0 then
+    return false
+end
+local id = redis.call('XADD', KEYS[1], unpack(ARGV, 4))
+if not id then
+    return redis.error_reply('ERR destination stream does not exist')
+end
+redis.call('XACK', KEYS[2], ARGV[1], ARGV[2])
+if ARGV[3] == '1' then
+    redis.call('XDEL', KEYS[2], ARGV[2])
+end
+return id
+";
+
+// KEYS[1] stream
+// ARGV[1] group, ARGV[2] consumer, ARGV[3] CLAIM or ACK, ARGV[4..] ids
+//
+// Only ids still pending for the consumer are claimed or acked, so ids
+// claimed by another consumer in the meantime are never taken back.
+// Returns the ids which were claimed or acked.
+const LEASE: &str = r"
+redis.replicate_commands()
+local held = {}
+for i = 4, #ARGV do
+    local pending = redis.call('XPENDING', KEYS[1], ARGV[1], ARGV[i], ARGV[i], 1)
+    if #pending == 1 and pending[1][2] == ARGV[2] then
+        if ARGV[3] == 'ACK' then
+            redis.call('XACK', KEYS[1], ARGV[1], ARGV[i])
+        else
+            redis.call('XCLAIM', KEYS[1], ARGV[1], ARGV[2], 0, ARGV[i], 'JUSTID')
+        end
+        held[#held + 1] = ARGV[i]
+    end
+end
+return held
+";
+
+// KEYS[1] stream
+// ARGV[1] number of XADD options, ARGV[2..] the options, then for
+// each message its number of field/values followed by the field/values
+//
+// Each XADD runs with pcall, so one failing message doesn't stop the
+// others. Returns an id, or an array holding the error, per message.
+const ADD_BATCH: &str = r"
+redis.replicate_commands()
+local options = {}
+local i = 2
+for _ = 1, tonumber(ARGV[1]) do
+    options[#options + 1] = ARGV[i]
+    i = i + 1
+end
+local ids = {}
+while i <= #ARGV do
+    local n = tonumber(ARGV[i])
+    local args = {KEYS[1]}
+    for _, option in ipairs(options) do
+        args[#args + 1] = option
+    end
+    args[#args + 1] = '*'
+    for j = i + 1, i + n do
+        args[#args + 1] = ARGV[j]
+    end
+    local id = redis.pcall('XADD', unpack(args))
+    if type(id) == 'table' and id.err then
+        ids[#ids + 1] = {id.err}
+    else
+        ids[#ids + 1] = id
+    end
+    i = i + n + 1
+end
+return ids
+";
+
+lazy_static! {
+    // `Script::new` hashes the source, so only do it once.
+    static ref ADD_ACK_SCRIPT: Script = Script::new(ADD_ACK);
+    static ref LEASE_SCRIPT: Script = Script::new(LEASE);
+    static ref ADD_BATCH_SCRIPT: Script = Script::new(ADD_BATCH);
+}
+
+pub(crate) fn add_ack_script() -> &'static Script {
+    &ADD_ACK_SCRIPT
+}
+
+pub(crate) fn add_ack_invocation<
+    'a,
+    DK: ToRedisArgs,
+    F: ToRedisArgs,
+    V: ToRedisArgs,
+    K: ToRedisArgs,
+    G: ToRedisArgs,
+    ID: ToRedisArgs,
+>(
+    script: &'a Script,
+    dest: DK,
+    items: &[(F, V)],
+    key: K,
+    group: G,
+    id: ID,
+    options: StreamAddAckOptions,
+) -> ScriptInvocation<'a> {
+    let mut invocation = script.key(dest);
+    invocation
+        .key(key)
+        .arg(group)
+        .arg(id)
+        .arg(if options.delete() { "1" } else { "0" })
+        .arg(options.xadd_args())
+        .arg(items);
+    invocation
+}
+
+/// `ack` acks the ids still pending for `consumer`, otherwise they're re-claimed.
+pub(crate) fn lease_invocation(
+    key: &str,
+    group: &str,
+    consumer: &str,
+    ack: bool,
+    ids: &[StreamEntryId],
+) -> ScriptInvocation<'static> {
+    let mut invocation = LEASE_SCRIPT.key(key);
+    invocation
+        .arg(group)
+        .arg(consumer)
+        .arg(if ack { "ACK" } else { "CLAIM" })
+        .arg(ids);
+    invocation
+}
+
+/// `options` are the `XADD` options and `messages` the field/values of each message.
+pub(crate) fn add_batch_invocation(
+    key: &str,
+    options: &[Vec<u8>],
+    messages: &[&[Vec<u8>]],
+) -> ScriptInvocation<'static> {
+    let mut invocation = ADD_BATCH_SCRIPT.key(key);
+    invocation.arg(options.len()).arg(options);
+    for args in messages {
+        invocation.arg(args.len()).arg(*args);
+    }
+    invocation
+}
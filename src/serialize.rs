@@ -0,0 +1,772 @@
+//! Optional `serde` support for stream messages.
+//!
+//! Structs (or maps) are flattened into stream field/value pairs on the way in
+//! and decoded from a `StreamId` on the way out. Field values are stored the same
+//! way `redis-rs` writes them: numbers as strings, booleans as `1`/`0` and
+//! `None` fields are skipped. Nested structs, sequences and maps aren't supported.
+
+use crate::types::{StreamEntryId, StreamId, StreamKey, StreamRangeReply, StreamReadReply};
+
+use redis::{from_redis_value, FromRedisValue, RedisError, RedisResult, Value};
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+
+use std::fmt;
+use std::str::from_utf8;
+
+/// Error raised while mapping between serde types and stream field/values.
+/// It's converted into a `RedisError` with a `TypeError` kind.
+#[derive(Debug)]
+struct FieldError(String);
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+impl ser::Error for FieldError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        FieldError(msg.to_string())
+    }
+}
+
+impl de::Error for FieldError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        FieldError(msg.to_string())
+    }
+}
+
+fn type_error(desc: &'static str, err: FieldError) -> RedisError {
+    RedisError::from((redis::ErrorKind::TypeError, desc, err.0))
+}
+
+/// Flatten a `Serialize` struct or map into stream field/value pairs.
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Event {
+///     name: String,
+///     count: u32,
+///     note: Option<String>,
+/// }
+///
+/// let event = Event { name: "signup".into(), count: 2, note: None };
+/// let fields = redis_streams::to_stream_fields(&event).unwrap();
+/// assert_eq!(fields[0], ("name".to_string(), b"signup".to_vec()));
+/// assert_eq!(fields[1], ("count".to_string(), b"2".to_vec()));
+/// assert_eq!(fields.len(), 2);
+/// ```
+///
+pub fn to_stream_fields<T: Serialize + ?Sized>(value: &T) -> RedisResult<Vec<(String, Vec<u8>)>> {
+    let mut fields = vec![];
+    value
+        .serialize(FieldsSerializer {
+            fields: &mut fields,
+        })
+        .map_err(|err| type_error("Failed to serialize stream fields", err))?;
+    Ok(fields)
+}
+
+impl StreamId {
+    /// Decode this message's field/values into `T`.
+    ///
+    /// Errors name the stream `id` and the missing or mistyped field.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> RedisResult<T> {
+        T::deserialize(StreamIdDeserializer { stream_id: self }).map_err(|err| {
+            RedisError::from((
+                redis::ErrorKind::TypeError,
+                "Failed to deserialize stream message",
+                format!("id {}: {}", self.id, err),
+            ))
+        })
+    }
+}
+
+/// A stream message decoded into `T`.
+#[derive(Default, Debug, Clone)]
+pub struct StreamTypedId<T> {
+    pub id: StreamEntryId,
+    pub data: T,
+}
+
+/// A stream `key` and its messages decoded into `T`.
+#[derive(Default, Debug, Clone)]
+pub struct StreamTypedKey<T> {
+    pub key: String,
+    pub ids: Vec<StreamTypedId<T>>,
+}
+
+/// Typed variant of [`StreamReadReply`].
+///
+/// [`StreamReadReply`]: ./struct.StreamReadReply.html
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamTypedReadReply<T> {
+    pub keys: Vec<StreamTypedKey<T>>,
+}
+
+/// Typed variant of [`StreamRangeReply`].
+///
+/// [`StreamRangeReply`]: ./struct.StreamRangeReply.html
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamTypedRangeReply<T> {
+    pub ids: Vec<StreamTypedId<T>>,
+}
+
+fn typed_ids<T: DeserializeOwned>(ids: &[StreamId]) -> RedisResult<Vec<StreamTypedId<T>>> {
+    ids.iter()
+        .map(|stream_id| {
+            Ok(StreamTypedId {
+                id: stream_id.id,
+                data: stream_id.deserialize()?,
+            })
+        })
+        .collect()
+}
+
+impl StreamKey {
+    /// Decode every message for this `key` into `T`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> RedisResult<StreamTypedKey<T>> {
+        Ok(StreamTypedKey {
+            key: self.key.clone(),
+            ids: typed_ids(&self.ids)?,
+        })
+    }
+}
+
+impl StreamReadReply {
+    /// Decode every message in this reply into `T`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> RedisResult<StreamTypedReadReply<T>> {
+        Ok(StreamTypedReadReply {
+            keys: self
+                .keys
+                .iter()
+                .map(|key| key.deserialize())
+                .collect::<RedisResult<_>>()?,
+        })
+    }
+}
+
+impl StreamRangeReply {
+    /// Decode every message in this reply into `T`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> RedisResult<StreamTypedRangeReply<T>> {
+        Ok(StreamTypedRangeReply {
+            ids: typed_ids(&self.ids)?,
+        })
+    }
+}
+
+impl<T: DeserializeOwned> FromRedisValue for StreamTypedReadReply<T> {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let reply: StreamReadReply = from_redis_value(v)?;
+        reply.deserialize()
+    }
+}
+
+impl<T: DeserializeOwned> FromRedisValue for StreamTypedRangeReply<T> {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let reply: StreamRangeReply = from_redis_value(v)?;
+        reply.deserialize()
+    }
+}
+
+// Serialization
+
+/// Top-level serializer: only structs and maps are accepted.
+struct FieldsSerializer<'a> {
+    fields: &'a mut Vec<(String, Vec<u8>)>,
+}
+
+fn top_level_error<T>() -> Result<T, FieldError> {
+    Err(FieldError(
+        "stream messages must be serialized from a struct or map".to_string(),
+    ))
+}
+
+macro_rules! reject_top_level {
+    ($($method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self $(, _: $arg)*) -> Result<Self::Ok, Self::Error> {
+                top_level_error()
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for FieldsSerializer<'a> {
+    type Ok = ();
+    type Error = FieldError;
+    type SerializeSeq = Impossible<(), FieldError>;
+    type SerializeTuple = Impossible<(), FieldError>;
+    type SerializeTupleStruct = Impossible<(), FieldError>;
+    type SerializeTupleVariant = Impossible<(), FieldError>;
+    type SerializeMap = FieldsMapSerializer<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), FieldError>;
+
+    reject_top_level!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    );
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), FieldError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), FieldError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), FieldError> {
+        top_level_error()
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, FieldError> {
+        top_level_error()
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, FieldError> {
+        top_level_error()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, FieldError> {
+        top_level_error()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, FieldError> {
+        top_level_error()
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, FieldError> {
+        Ok(FieldsMapSerializer {
+            fields: self.fields,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, FieldError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, FieldError> {
+        top_level_error()
+    }
+}
+
+impl<'a> ser::SerializeStruct for FieldsSerializer<'a> {
+    type Ok = ();
+    type Error = FieldError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FieldError> {
+        if let Some(bytes) = value.serialize(ValueSerializer { field: key })? {
+            self.fields.push((key.to_string(), bytes));
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), FieldError> {
+        Ok(())
+    }
+}
+
+struct FieldsMapSerializer<'a> {
+    fields: &'a mut Vec<(String, Vec<u8>)>,
+    key: Option<String>,
+}
+
+impl<'a> ser::SerializeMap for FieldsMapSerializer<'a> {
+    type Ok = ();
+    type Error = FieldError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), FieldError> {
+        let bytes = key
+            .serialize(ValueSerializer { field: "<map key>" })?
+            .ok_or_else(|| FieldError("map keys can't be none".to_string()))?;
+        let key = String::from_utf8(bytes)
+            .map_err(|_| FieldError("map keys must be valid utf8".to_string()))?;
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), FieldError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| FieldError("map value serialized before its key".to_string()))?;
+        if let Some(bytes) = value.serialize(ValueSerializer { field: &key })? {
+            self.fields.push((key, bytes));
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), FieldError> {
+        Ok(())
+    }
+}
+
+/// Serializes a single field value. `None` means the field is skipped.
+struct ValueSerializer<'a> {
+    field: &'a str,
+}
+
+impl<'a> ValueSerializer<'a> {
+    fn nested<T>(&self) -> Result<T, FieldError> {
+        Err(FieldError(format!(
+            "field `{}` is a nested value, which can't be stored in a stream field",
+            self.field
+        )))
+    }
+}
+
+macro_rules! serialize_display {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+                Ok(Some(v.to_string().into_bytes()))
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for ValueSerializer<'a> {
+    type Ok = Option<Vec<u8>>;
+    type Error = FieldError;
+    type SerializeSeq = Impossible<Option<Vec<u8>>, FieldError>;
+    type SerializeTuple = Impossible<Option<Vec<u8>>, FieldError>;
+    type SerializeTupleStruct = Impossible<Option<Vec<u8>>, FieldError>;
+    type SerializeTupleVariant = Impossible<Option<Vec<u8>>, FieldError>;
+    type SerializeMap = Impossible<Option<Vec<u8>>, FieldError>;
+    type SerializeStruct = Impossible<Option<Vec<u8>>, FieldError>;
+    type SerializeStructVariant = Impossible<Option<Vec<u8>>, FieldError>;
+
+    serialize_display!(
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    );
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, FieldError> {
+        Ok(Some(if v { b"1".to_vec() } else { b"0".to_vec() }))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, FieldError> {
+        Ok(Some(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, FieldError> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, FieldError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, FieldError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, FieldError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, FieldError> {
+        Ok(Some(variant.as_bytes().to_vec()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, FieldError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, FieldError> {
+        self.nested()
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, FieldError> {
+        self.nested()
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, FieldError> {
+        self.nested()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, FieldError> {
+        self.nested()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, FieldError> {
+        self.nested()
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, FieldError> {
+        self.nested()
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, FieldError> {
+        self.nested()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, FieldError> {
+        self.nested()
+    }
+}
+
+// Deserialization
+
+/// Deserializes a `StreamId` as a map of its field/values.
+struct StreamIdDeserializer<'a> {
+    stream_id: &'a StreamId,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for StreamIdDeserializer<'a> {
+    type Error = FieldError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        visitor.visit_map(FieldsAccess {
+            fields: self.stream_id.fields.iter().map(|(f, v)| (f, v)),
+            value: None,
+        })
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, FieldError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct FieldsAccess<'a, I> {
+    fields: I,
+    value: Option<(&'a str, &'a Value)>,
+}
+
+impl<'de, 'a, I> de::MapAccess<'de> for FieldsAccess<'a, I>
+where
+    I: Iterator<Item = (&'a String, &'a Value)>,
+{
+    type Error = FieldError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, FieldError> {
+        match self.fields.next() {
+            Some((field, value)) => {
+                self.value = Some((field, value));
+                seed.deserialize(field.as_str().into_deserializer())
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, FieldError> {
+        let (field, value) = self
+            .value
+            .take()
+            .ok_or_else(|| FieldError("field value requested before its name".to_string()))?;
+        seed.deserialize(ValueDeserializer { field, value })
+    }
+}
+
+/// Deserializes a single field value.
+struct ValueDeserializer<'a> {
+    field: &'a str,
+    value: &'a Value,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    fn invalid(&self, expected: &str) -> FieldError {
+        let got = match *self.value {
+            Value::Data(ref bytes) => format!("{:?}", String::from_utf8_lossy(bytes)),
+            ref value => format!("{:?}", value),
+        };
+        FieldError(format!(
+            "invalid value for field `{}`: expected {}, got {}",
+            self.field, expected, got
+        ))
+    }
+
+    fn bytes(&self) -> Result<&'a [u8], FieldError> {
+        match *self.value {
+            Value::Data(ref bytes) => Ok(bytes),
+            Value::Status(ref s) => Ok(s.as_bytes()),
+            _ => Err(self.invalid("bulk data")),
+        }
+    }
+
+    fn text(&self, expected: &str) -> Result<&'a str, FieldError> {
+        from_utf8(self.bytes()?).map_err(|_| self.invalid(expected))
+    }
+
+    fn parse<T: std::str::FromStr>(&self, expected: &str) -> Result<T, FieldError> {
+        match *self.value {
+            Value::Int(v) => v.to_string().parse().map_err(|_| self.invalid(expected)),
+            _ => self
+                .text(expected)?
+                .parse()
+                .map_err(|_| self.invalid(expected)),
+        }
+    }
+
+    fn nested<T>(&self) -> Result<T, FieldError> {
+        Err(FieldError(format!(
+            "field `{}` is a nested value, which can't be read from a stream field",
+            self.field
+        )))
+    }
+}
+
+macro_rules! deserialize_parse {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = FieldError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        match *self.value {
+            Value::Int(v) => visitor.visit_i64(v),
+            Value::Nil => visitor.visit_none(),
+            _ => match from_utf8(self.bytes()?) {
+                Ok(s) => visitor.visit_str(s),
+                Err(_) => visitor.visit_bytes(self.bytes()?),
+            },
+        }
+    }
+
+    deserialize_parse!(
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+        deserialize_char => visit_char(char),
+    );
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        match *self.value {
+            Value::Int(v) => visitor.visit_bool(v != 0),
+            _ => match self.text("bool")? {
+                "1" | "true" => visitor.visit_bool(true),
+                "0" | "false" => visitor.visit_bool(false),
+                _ => Err(self.invalid("bool")),
+            },
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        visitor.visit_str(self.text("utf8 string")?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        visitor.visit_bytes(self.bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        match *self.value {
+            Value::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, FieldError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, FieldError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FieldError> {
+        // only unit variants, stored by name
+        let variant: de::value::StrDeserializer<FieldError> =
+            self.text("enum variant")?.into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, _: V) -> Result<V::Value, FieldError> {
+        self.nested()
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _: usize, _: V) -> Result<V::Value, FieldError> {
+        self.nested()
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        _: V,
+    ) -> Result<V::Value, FieldError> {
+        self.nested()
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _: V) -> Result<V::Value, FieldError> {
+        self.nested()
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, FieldError> {
+        self.nested()
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FieldError> {
+        visitor.visit_unit()
+    }
+}
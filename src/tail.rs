@@ -0,0 +1,168 @@
+use crate::commands::AsyncStreamCommands;
+use crate::cursor::StreamCursor;
+use crate::types::{StreamId, StreamRangeReply, StreamReadOptions, StreamReadReply};
+
+use futures::stream::{self, Stream};
+use futures_timer::Delay;
+use redis::aio::Connection;
+use redis::{Client, ErrorKind, RedisError, RedisResult};
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// Backoff between reconnects, doubled after each failure.
+const RETRY_MIN: Duration = Duration::from_millis(100);
+const RETRY_MAX: Duration = Duration::from_secs(5);
+
+struct TailState {
+    client: Client,
+    con: Option<Connection>,
+    cursor: StreamCursor,
+    options: StreamReadOptions,
+    buffer: VecDeque<(String, StreamId)>,
+    /// Yielded before anything else, then the stream ends.
+    error: Option<RedisError>,
+    /// How long to wait before the next reconnect, if the last one failed.
+    retry: Option<Duration>,
+    done: bool,
+}
+
+/// Tail `keys` as a `futures::Stream` of `(key, message)` pairs.
+///
+/// Each key starts reading after its id in `start_ids` and its cursor
+/// advances to the last message returned for it, so nothing is read
+/// twice. `$` is resolved to the key's last id on the first read, which
+/// means messages added while reconnecting aren't skipped. With a consumer
+/// `group`, `>` ids are left as they are and Redis tracks the position.
+///
+/// Set `block` on `options`, otherwise the stream polls Redis in a tight loop.
+///
+/// Errors are yielded as items. Connection errors (dropped, refused, IO
+/// or timeouts), whether from a read or a reconnect, don't end the stream:
+/// the next poll waits for a backoff, from 100ms doubling up to 5s, then
+/// reconnects with `client` and resumes from the last seen ids. Any other
+/// error, or `keys` and `start_ids` of different lengths, ends the stream
+/// after it's yielded.
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use redis_streams::{client_open,tail,StreamReadOptions};
+/// # async fn run() {
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+///
+/// let opts = StreamReadOptions::default().block(5000).count(100);
+/// let mut messages = Box::pin(tail(client, &["k1", "k2"], &["$", "0"], opts));
+/// while let Some(item) = messages.next().await {
+///     let (key, msg) = item.unwrap();
+///     println!("{} {}", key, msg.id);
+/// }
+/// # }
+/// ```
+///
+pub fn tail(
+    client: Client,
+    keys: &[&str],
+    start_ids: &[&str],
+    options: StreamReadOptions,
+) -> impl Stream<Item = RedisResult<(String, StreamId)>> {
+    let mut cursor = StreamCursor::new();
+    for (key, id) in keys.iter().zip(start_ids.iter()) {
+        cursor.insert(key, id);
+    }
+    let error = if keys.len() != start_ids.len() {
+        Some(RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "tail needs one start id per key",
+        )))
+    } else {
+        None
+    };
+    let state = TailState {
+        client,
+        con: None,
+        cursor,
+        options,
+        buffer: VecDeque::new(),
+        error,
+        retry: None,
+        done: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        let result = state.next().await;
+        Some((result, state))
+    })
+}
+
+impl TailState {
+    async fn next(&mut self) -> RedisResult<(String, StreamId)> {
+        if let Some(err) = self.error.take() {
+            self.done = true;
+            return Err(err);
+        }
+        loop {
+            if let Some(item) = self.buffer.pop_front() {
+                return Ok(item);
+            }
+            if let Some(delay) = self.retry {
+                Delay::new(delay).await;
+            }
+            match self.read().await {
+                Ok(()) => self.retry = None,
+                Err(err) => {
+                    if is_connection_error(&err) {
+                        self.con = None;
+                        self.retry = Some(match self.retry {
+                            Some(delay) => (delay * 2).min(RETRY_MAX),
+                            None => RETRY_MIN,
+                        });
+                    } else {
+                        self.done = true;
+                    }
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    async fn read(&mut self) -> RedisResult<()> {
+        let mut con = match self.con.take() {
+            Some(con) => con,
+            None => self.client.get_async_connection().await?,
+        };
+        let result = self.read_with(&mut con).await;
+        self.con = Some(con);
+        result
+    }
+
+    async fn read_with(&mut self, con: &mut Connection) -> RedisResult<()> {
+        for key in self.cursor.latest_keys() {
+            let reply: StreamRangeReply = con.xrevrange_count(&key, "+", "-", 1).await?;
+            self.cursor.resolve_latest(&key, &reply);
+        }
+
+        let reply: StreamReadReply = con
+            .xread_options(
+                &self.cursor.keys(),
+                &self.cursor.ids(),
+                self.options.clone(),
+            )
+            .await?;
+        self.cursor.update(&reply);
+        for stream_key in reply.keys {
+            for message in stream_key.ids {
+                self.buffer.push_back((stream_key.key.clone(), message));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_connection_error(err: &RedisError) -> bool {
+    err.is_connection_dropped()
+        || err.is_connection_refusal()
+        || err.is_io_error()
+        || err.is_timeout()
+}
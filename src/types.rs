@@ -1,7 +1,271 @@
-use redis::{from_redis_value, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs, Value};
+use redis::{
+    from_redis_value, FromRedisValue, RedisError, RedisResult, RedisWrite, ToRedisArgs, Value,
+};
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::io::{Error, ErrorKind};
+use std::fmt;
+use std::str::{from_utf8, FromStr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Stream Entry Id
+
+/// A stream message `id` in the form of `<ms>-<seq>`.
+///
+/// Ids are ordered the same way Redis orders them, so they can be compared,
+/// sorted and stepped with `next` and `prev`.
+///
+/// ```
+/// use redis_streams::StreamEntryId;
+/// let id: StreamEntryId = "1000-1".parse().unwrap();
+/// assert_eq!(id, StreamEntryId::new(1000, 1));
+/// assert_eq!(id.next().unwrap().to_string(), "1000-2");
+/// assert!(id < "1001-0".parse().unwrap());
+/// ```
+///
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamEntryId {
+    /// Unix time in milliseconds.
+    pub ms: u64,
+    /// Sequence number within the millisecond.
+    pub seq: u64,
+}
+
+impl StreamEntryId {
+    /// The smallest possible id (`0-0`).
+    pub const MIN: StreamEntryId = StreamEntryId { ms: 0, seq: 0 };
+    /// The largest possible id.
+    pub const MAX: StreamEntryId = StreamEntryId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    pub fn new(ms: u64, seq: u64) -> Self {
+        StreamEntryId { ms, seq }
+    }
+
+    /// Returns the id which directly follows this one,
+    /// or `None` if this is the largest possible id.
+    pub fn next(&self) -> Option<Self> {
+        if self.seq < u64::MAX {
+            Some(StreamEntryId::new(self.ms, self.seq + 1))
+        } else if self.ms < u64::MAX {
+            Some(StreamEntryId::new(self.ms + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the id which directly precedes this one,
+    /// or `None` if this is `0-0`.
+    pub fn prev(&self) -> Option<Self> {
+        if self.seq > 0 {
+            Some(StreamEntryId::new(self.ms, self.seq - 1))
+        } else if self.ms > 0 {
+            Some(StreamEntryId::new(self.ms - 1, u64::MAX))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the first id for the millisecond of `time`.
+    /// Times before the unix epoch map to `0-0`.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let ms = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        StreamEntryId::new(ms as u64, 0)
+    }
+
+    /// Returns the time this id was generated at, ignoring the sequence number.
+    pub fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.ms)
+    }
+}
+
+impl Ord for StreamEntryId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.ms, self.seq).cmp(&(other.ms, other.seq))
+    }
+}
+
+impl PartialOrd for StreamEntryId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for StreamEntryId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+impl FromStr for StreamEntryId {
+    type Err = RedisError;
+
+    /// Parses `<ms>-<seq>` or a bare `<ms>`, which Redis treats as `<ms>-0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '-');
+        let ms = parts.next().and_then(|ms| ms.parse::<u64>().ok());
+        let seq = match parts.next() {
+            Some(seq) => seq.parse::<u64>().ok(),
+            None => Some(0),
+        };
+        match (ms, seq) {
+            (Some(ms), Some(seq)) => Ok(StreamEntryId::new(ms, seq)),
+            _ => Err(RedisError::from((
+                redis::ErrorKind::TypeError,
+                "Invalid stream id",
+                format!("expected <ms>-<seq> but got {:?}", s),
+            ))),
+        }
+    }
+}
+
+impl From<(u64, u64)> for StreamEntryId {
+    fn from(parts: (u64, u64)) -> Self {
+        StreamEntryId::new(parts.0, parts.1)
+    }
+}
+
+impl PartialEq<str> for StreamEntryId {
+    fn eq(&self, other: &str) -> bool {
+        other.parse::<StreamEntryId>().ok().as_ref() == Some(self)
+    }
+}
+
+impl<'a> PartialEq<&'a str> for StreamEntryId {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<String> for StreamEntryId {
+    fn eq(&self, other: &String) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl ToRedisArgs for StreamEntryId {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(self.to_string().as_bytes());
+    }
+}
+
+// redis has no blanket impl for references, so `just_ids` results
+// can't be passed back as args without this.
+impl ToRedisArgs for &StreamEntryId {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        (*self).write_redis_args(out)
+    }
+}
+
+impl FromRedisValue for StreamEntryId {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        match *v {
+            Value::Data(ref bytes) => match from_utf8(bytes) {
+                Ok(s) => s.parse(),
+                Err(_) => Err(RedisError::from((
+                    redis::ErrorKind::TypeError,
+                    "Invalid stream id",
+                    "stream id isn't valid utf8".to_string(),
+                ))),
+            },
+            Value::Status(ref s) => s.parse(),
+            _ => Err(RedisError::from((
+                redis::ErrorKind::TypeError,
+                "Invalid stream id",
+                format!("expected bulk data but got {:?}", v),
+            ))),
+        }
+    }
+}
+
+/// A stream `id` argument, including the special markers Redis accepts
+/// in place of an id.
+///
+/// Any `StreamCommands` method which takes an `id` accepts this type.
+///
+/// ```no_run
+/// use redis_streams::{client_open,StreamCommands,StreamIdSpec,StreamRangeReply};
+/// let client = client_open("redis://127.0.0.1/0").unwrap();
+/// let mut con = client.get_connection().unwrap();
+///
+/// let reply: StreamRangeReply = con
+///     .xrange("k1", StreamIdSpec::Min, StreamIdSpec::Max)
+///     .unwrap();
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamIdSpec {
+    /// An explicit `<ms>-<seq>` id.
+    Id(StreamEntryId),
+    /// An exclusive range bound (`(<ms>-<seq>`). Requires Redis 6.2 or newer.
+    Exclusive(StreamEntryId),
+    /// The smallest id in a stream (`-`).
+    Min,
+    /// The largest id in a stream (`+`).
+    Max,
+    /// The last id added to a stream (`$`).
+    Last,
+    /// Messages never delivered to a consumer group (`>`).
+    Undelivered,
+    /// Let Redis generate the id when adding a message (`*`).
+    Auto,
+}
+
+impl From<StreamEntryId> for StreamIdSpec {
+    fn from(id: StreamEntryId) -> Self {
+        StreamIdSpec::Id(id)
+    }
+}
+
+impl fmt::Display for StreamIdSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StreamIdSpec::Id(ref id) => write!(f, "{}", id),
+            StreamIdSpec::Exclusive(ref id) => write!(f, "({}", id),
+            StreamIdSpec::Min => f.write_str("-"),
+            StreamIdSpec::Max => f.write_str("+"),
+            StreamIdSpec::Last => f.write_str("$"),
+            StreamIdSpec::Undelivered => f.write_str(">"),
+            StreamIdSpec::Auto => f.write_str("*"),
+        }
+    }
+}
+
+impl FromStr for StreamIdSpec {
+    type Err = RedisError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "-" => Ok(StreamIdSpec::Min),
+            "+" => Ok(StreamIdSpec::Max),
+            "$" => Ok(StreamIdSpec::Last),
+            ">" => Ok(StreamIdSpec::Undelivered),
+            "*" => Ok(StreamIdSpec::Auto),
+            _ if s.starts_with('(') => Ok(StreamIdSpec::Exclusive(s[1..].parse()?)),
+            _ => Ok(StreamIdSpec::Id(s.parse()?)),
+        }
+    }
+}
+
+impl ToRedisArgs for StreamIdSpec {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(self.to_string().as_bytes());
+    }
+}
 
 // Stream Maxlen Enum
 
@@ -29,6 +293,211 @@ impl ToRedisArgs for StreamMaxlen {
     }
 }
 
+/// Which entries [`StreamTrimOptions`] evicts.
+///
+/// [`StreamTrimOptions`]: ./struct.StreamTrimOptions.html
+///
+#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+pub enum StreamTrimStrategy {
+    /// Evict the oldest entries until the stream has at most this many entries.
+    MaxLen(usize),
+    /// Evict entries with an `id` lower than this one.
+    MinId(StreamEntryId),
+}
+
+/// Builder options for trimming a stream with
+/// [`xtrim_options`] or as part of [`StreamAddOptions`].
+///
+/// Renders `<MAXLEN | MINID> [= | ~] <threshold> [LIMIT <count>]`.
+///
+/// [`xtrim_options`]: ./trait.StreamCommands.html#method.xtrim_options
+/// [`StreamAddOptions`]: ./struct.StreamAddOptions.html
+///
+#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+pub struct StreamTrimOptions {
+    strategy: StreamTrimStrategy,
+    /// Set `~` instead of `=`.
+    approx: bool,
+    /// Set LIMIT <count> cmd arg.
+    limit: Option<usize>,
+}
+
+impl StreamTrimOptions {
+    /// Trim by `MAXLEN`. Requires Redis 5 or newer.
+    pub fn maxlen(count: usize) -> Self {
+        StreamTrimOptions {
+            strategy: StreamTrimStrategy::MaxLen(count),
+            approx: false,
+            limit: None,
+        }
+    }
+
+    /// Trim by `MINID`. Requires Redis 6.2 or newer.
+    pub fn minid<ID: Into<StreamEntryId>>(id: ID) -> Self {
+        StreamTrimOptions {
+            strategy: StreamTrimStrategy::MinId(id.into()),
+            approx: false,
+            limit: None,
+        }
+    }
+
+    /// Trim to exactly the threshold (`=`). This is the default.
+    pub fn exact(mut self) -> Self {
+        self.approx = false;
+        self
+    }
+
+    /// Let Redis trim lazily, in whole macro nodes (`~`).
+    pub fn approx(mut self) -> Self {
+        self.approx = true;
+        self
+    }
+
+    /// Cap the number of entries evicted by a single call.
+    /// Redis only accepts `LIMIT` along with `approx`.
+    pub fn limit(mut self, count: usize) -> Self {
+        self.limit = Some(count);
+        self
+    }
+
+    pub fn strategy(&self) -> StreamTrimStrategy {
+        self.strategy
+    }
+}
+
+impl From<StreamMaxlen> for StreamTrimOptions {
+    fn from(maxlen: StreamMaxlen) -> Self {
+        match maxlen {
+            StreamMaxlen::Equals(v) => StreamTrimOptions::maxlen(v),
+            StreamMaxlen::Aprrox(v) => StreamTrimOptions::maxlen(v).approx(),
+        }
+    }
+}
+
+impl ToRedisArgs for StreamTrimOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let ch = if self.approx { "~" } else { "=" };
+        match self.strategy {
+            StreamTrimStrategy::MaxLen(v) => {
+                out.write_arg("MAXLEN".as_bytes());
+                out.write_arg(ch.as_bytes());
+                v.write_redis_args(out);
+            }
+            StreamTrimStrategy::MinId(ref id) => {
+                out.write_arg("MINID".as_bytes());
+                out.write_arg(ch.as_bytes());
+                id.write_redis_args(out);
+            }
+        }
+        if let Some(ref count) = self.limit {
+            out.write_arg("LIMIT".as_bytes());
+            out.write_arg(format!("{}", count).as_bytes());
+        }
+    }
+}
+
+/// Builder options for [`xadd_options`] command.
+///
+/// ```
+/// use redis_streams::{StreamAddOptions,StreamTrimOptions};
+///
+/// // XADD <key> NOMKSTREAM MAXLEN ~ 1000 LIMIT 100 * ...
+/// let opts = StreamAddOptions::default()
+///     .nomkstream()
+///     .trim(StreamTrimOptions::maxlen(1000).approx().limit(100));
+/// ```
+///
+/// [`xadd_options`]: ./trait.StreamCommands.html#method.xadd_options
+///
+#[derive(Default, Debug, Clone, Copy)]
+pub struct StreamAddOptions {
+    /// Set NOMKSTREAM cmd arg.
+    nomkstream: bool,
+    /// Set the trimming cmd args.
+    trim: Option<StreamTrimOptions>,
+    /// Set an explicit message id. Defaults to `*`.
+    id: Option<StreamEntryId>,
+}
+
+impl StreamAddOptions {
+    /// Don't create the stream if it doesn't exist.
+    /// Redis replies with nil instead of an id in that case.
+    /// Requires Redis 6.2 or newer.
+    pub fn nomkstream(mut self) -> Self {
+        self.nomkstream = true;
+        self
+    }
+
+    pub fn trim<T: Into<StreamTrimOptions>>(mut self, trim: T) -> Self {
+        self.trim = Some(trim.into());
+        self
+    }
+
+    pub fn id<ID: Into<StreamEntryId>>(mut self, id: ID) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Let Redis generate the message id (`*`). This is the default.
+    pub fn auto_id(mut self) -> Self {
+        self.id = None;
+        self
+    }
+}
+
+impl ToRedisArgs for StreamAddOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if self.nomkstream {
+            out.write_arg("NOMKSTREAM".as_bytes());
+        }
+        if let Some(ref trim) = self.trim {
+            trim.write_redis_args(out);
+        }
+        match self.id {
+            Some(ref id) => id.write_redis_args(out),
+            None => out.write_arg("*".as_bytes()),
+        }
+    }
+}
+
+/// Builder options for [`xadd_xack`] command.
+///
+/// [`xadd_xack`]: ./trait.StreamCommands.html#method.xadd_xack
+///
+#[derive(Default, Debug, Clone, Copy)]
+pub struct StreamAddAckOptions {
+    /// Set the XADD cmd args for the destination stream.
+    add: StreamAddOptions,
+    /// XDEL the source message after acking it.
+    delete: bool,
+}
+
+impl StreamAddAckOptions {
+    pub fn add_options(mut self, options: StreamAddOptions) -> Self {
+        self.add = options;
+        self
+    }
+
+    pub fn with_delete(mut self) -> Self {
+        self.delete = true;
+        self
+    }
+
+    pub(crate) fn xadd_args(&self) -> StreamAddOptions {
+        self.add
+    }
+
+    pub(crate) fn delete(&self) -> bool {
+        self.delete
+    }
+}
+
 /// Builder options for [`xclaim_options`] command.
 ///
 /// [`xclaim_options`]: ./trait.StreamCommands.html#method.xclaim_options
@@ -101,11 +570,211 @@ impl ToRedisArgs for StreamClaimOptions {
     }
 }
 
+/// Builder options for [`xautoclaim_options`] command.
+///
+/// [`xautoclaim_options`]: ./trait.StreamCommands.html#method.xautoclaim_options
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamAutoClaimOptions {
+    /// Set COUNT <count> cmd arg.
+    count: Option<usize>,
+    /// Set JUSTID cmd arg. The claimed `StreamId`s
+    /// are returned without their field/values.
+    justid: bool,
+}
+
+impl StreamAutoClaimOptions {
+    pub fn count(mut self, n: usize) -> Self {
+        self.count = Some(n);
+        self
+    }
+
+    pub fn with_justid(mut self) -> Self {
+        self.justid = true;
+        self
+    }
+}
+
+impl ToRedisArgs for StreamAutoClaimOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(ref n) = self.count {
+            out.write_arg("COUNT".as_bytes());
+            out.write_arg(format!("{}", n).as_bytes());
+        }
+        if self.justid {
+            out.write_arg("JUSTID".as_bytes());
+        }
+    }
+}
+
+/// Builder options for [`xpending_options`] command.
+///
+/// Defaults to the whole stream (`-` to `+`) with a count of 100.
+///
+/// [`xpending_options`]: ./trait.StreamCommands.html#method.xpending_options
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamPendingOptions {
+    /// Set the IDLE <min-idle-time> cmd arg.
+    idle: Option<usize>,
+    /// Set the <start> <end> cmd args.
+    range: Option<Vec<Vec<u8>>>,
+    /// Set the <count> cmd arg.
+    count: Option<usize>,
+    /// Set the <consumer> cmd arg.
+    consumer: Option<Vec<Vec<u8>>>,
+}
+
+impl StreamPendingOptions {
+    /// Only return entries idle for at least `ms` milliseconds.
+    /// Requires Redis 6.2+.
+    pub fn idle(mut self, ms: usize) -> Self {
+        self.idle = Some(ms);
+        self
+    }
+
+    pub fn range<S: ToRedisArgs, E: ToRedisArgs>(mut self, start: S, end: E) -> Self {
+        let mut range = ToRedisArgs::to_redis_args(&start);
+        range.extend(ToRedisArgs::to_redis_args(&end));
+        self.range = Some(range);
+        self
+    }
+
+    pub fn count(mut self, n: usize) -> Self {
+        self.count = Some(n);
+        self
+    }
+
+    pub fn consumer<CN: ToRedisArgs>(mut self, consumer_name: CN) -> Self {
+        self.consumer = Some(ToRedisArgs::to_redis_args(&consumer_name));
+        self
+    }
+}
+
+impl ToRedisArgs for StreamPendingOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(ref ms) = self.idle {
+            out.write_arg("IDLE".as_bytes());
+            out.write_arg(format!("{}", ms).as_bytes());
+        }
+
+        match self.range {
+            Some(ref range) => {
+                for i in range {
+                    out.write_arg(i);
+                }
+            }
+            None => {
+                out.write_arg("-".as_bytes());
+                out.write_arg("+".as_bytes());
+            }
+        }
+
+        out.write_arg(format!("{}", self.count.unwrap_or(100)).as_bytes());
+
+        if let Some(ref consumer) = self.consumer {
+            for i in consumer {
+                out.write_arg(i);
+            }
+        }
+    }
+}
+
+/// Builder options for [`xgroup_create_options`] command.
+///
+/// [`xgroup_create_options`]: ./trait.StreamCommands.html#method.xgroup_create_options
+///
+#[derive(Default, Debug, Clone, Copy)]
+pub struct StreamGroupCreateOptions {
+    /// Set the MKSTREAM cmd arg.
+    mkstream: bool,
+    /// Set the ENTRIESREAD <entries-read> cmd arg.
+    entries_read: Option<usize>,
+}
+
+impl StreamGroupCreateOptions {
+    /// Create the stream if it doesn't exist.
+    pub fn mkstream(mut self) -> Self {
+        self.mkstream = true;
+        self
+    }
+
+    /// Set how many entries the group has already read, so Redis can
+    /// report its `lag`. Requires Redis 7 or newer.
+    pub fn entries_read(mut self, n: usize) -> Self {
+        self.entries_read = Some(n);
+        self
+    }
+}
+
+impl ToRedisArgs for StreamGroupCreateOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if self.mkstream {
+            out.write_arg("MKSTREAM".as_bytes());
+        }
+        if let Some(ref n) = self.entries_read {
+            out.write_arg("ENTRIESREAD".as_bytes());
+            out.write_arg(format!("{}", n).as_bytes());
+        }
+    }
+}
+
+/// Builder options for [`xsetid_options`] command.
+///
+/// Both options require Redis 7 or newer.
+///
+/// [`xsetid_options`]: ./trait.StreamCommands.html#method.xsetid_options
+///
+#[derive(Default, Debug, Clone, Copy)]
+pub struct StreamSetIdOptions {
+    /// Set the ENTRIESADDED <entries-added> cmd arg.
+    entries_added: Option<usize>,
+    /// Set the MAXDELETEDID <max-deleted-id> cmd arg.
+    max_deleted_id: Option<StreamEntryId>,
+}
+
+impl StreamSetIdOptions {
+    pub fn entries_added(mut self, n: usize) -> Self {
+        self.entries_added = Some(n);
+        self
+    }
+
+    pub fn max_deleted_id<ID: Into<StreamEntryId>>(mut self, id: ID) -> Self {
+        self.max_deleted_id = Some(id.into());
+        self
+    }
+}
+
+impl ToRedisArgs for StreamSetIdOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(ref n) = self.entries_added {
+            out.write_arg("ENTRIESADDED".as_bytes());
+            out.write_arg(format!("{}", n).as_bytes());
+        }
+        if let Some(ref id) = self.max_deleted_id {
+            out.write_arg("MAXDELETEDID".as_bytes());
+            id.write_redis_args(out);
+        }
+    }
+}
+
 /// Builder options for [`xread_options`] command.
 ///
 /// [`xread_options`]: ./trait.StreamCommands.html#method.xread_options
 ///
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct StreamReadOptions {
     /// Set the BLOCK <milliseconds> cmd arg.
     block: Option<usize>,
@@ -207,6 +876,26 @@ pub struct StreamRangeReply {
     pub ids: Vec<StreamId>,
 }
 
+/// Binary-safe variant of [`StreamReadReply`].
+/// Stream keys and field names are kept as raw bytes.
+///
+/// [`StreamReadReply`]: ./struct.StreamReadReply.html
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamBytesReadReply {
+    pub keys: Vec<StreamBytesKey>,
+}
+
+/// Binary-safe variant of [`StreamRangeReply`].
+/// Field names are kept as raw bytes.
+///
+/// [`StreamRangeReply`]: ./struct.StreamRangeReply.html
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamBytesRangeReply {
+    pub ids: Vec<StreamBytesId>,
+}
+
 /// Reply type used with [`xclaim`] command.
 ///
 /// [`xclaim`]: ./trait.StreamCommands.html#method.xclaim
@@ -216,6 +905,26 @@ pub struct StreamClaimReply {
     pub ids: Vec<StreamId>,
 }
 
+/// Reply type used with [`xautoclaim`] and [`xautoclaim_options`] commands.
+///
+/// Call the command again with `next_stream_id` as the start `id` to continue
+/// scanning the pending entries list. A `next_stream_id` of `0-0`
+/// means the whole list has been scanned.
+///
+/// [`xautoclaim`]: ./trait.StreamCommands.html#method.xautoclaim
+/// [`xautoclaim_options`]: ./trait.StreamCommands.html#method.xautoclaim_options
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamAutoClaimReply {
+    pub next_stream_id: StreamEntryId,
+    /// The claimed messages. With `JUSTID`, only the `id` is set.
+    pub claimed: Vec<StreamId>,
+    /// Pending ids which no longer exist in the stream.
+    /// Redis removes these from the pending entries list.
+    /// Only returned by Redis 7 or newer.
+    pub deleted_ids: Vec<StreamEntryId>,
+}
+
 /// Reply type used with [`xpending`] command.
 ///
 /// [`xpending`]: ./trait.StreamCommands.html#method.xpending
@@ -245,8 +954,8 @@ impl StreamPendingReply {
 #[derive(Default, Debug, Clone)]
 pub struct StreamPendingData {
     pub count: usize,
-    pub start_id: String,
-    pub end_id: String,
+    pub start_id: StreamEntryId,
+    pub end_id: StreamEntryId,
     pub consumers: Vec<StreamInfoConsumer>,
 }
 
@@ -265,14 +974,99 @@ pub struct StreamPendingCountReply {
 ///
 /// [`xinfo_stream`]: ./trait.StreamCommands.html#method.xinfo_stream
 ///
+/// Fields added after Redis 5 are `None` when the server doesn't return them.
+/// Any fields this crate doesn't know about are kept in `extra`.
+///
 #[derive(Default, Debug, Clone)]
 pub struct StreamInfoStreamReply {
-    pub last_generated_id: String,
+    pub last_generated_id: StreamEntryId,
     pub radix_tree_keys: usize,
+    pub radix_tree_nodes: usize,
     pub groups: usize,
     pub length: usize,
     pub first_entry: StreamId,
     pub last_entry: StreamId,
+    /// Redis 7+
+    pub max_deleted_entry_id: Option<StreamEntryId>,
+    /// Redis 7+
+    pub entries_added: Option<usize>,
+    /// Redis 7+
+    pub recorded_first_entry_id: Option<StreamEntryId>,
+    pub extra: HashMap<String, Value>,
+}
+
+/// Reply type used with [`xinfo_stream_full`] and [`xinfo_stream_full_count`] commands.
+///
+/// Fields added after Redis 6 are `None` when the server doesn't return them.
+/// Any fields this crate doesn't know about are kept in `extra`.
+///
+/// [`xinfo_stream_full`]: ./trait.StreamCommands.html#method.xinfo_stream_full
+/// [`xinfo_stream_full_count`]: ./trait.StreamCommands.html#method.xinfo_stream_full_count
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoStreamFullReply {
+    pub length: usize,
+    pub radix_tree_keys: usize,
+    pub radix_tree_nodes: usize,
+    pub last_generated_id: StreamEntryId,
+    /// Redis 7+
+    pub max_deleted_entry_id: Option<StreamEntryId>,
+    /// Redis 7+
+    pub entries_added: Option<usize>,
+    /// Redis 7+
+    pub recorded_first_entry_id: Option<StreamEntryId>,
+    pub entries: Vec<StreamId>,
+    pub groups: Vec<StreamInfoGroupFull>,
+    pub extra: HashMap<String, Value>,
+}
+
+/// A group parsed from [`xinfo_stream_full`] command, including its
+/// pending entries list and consumers.
+///
+/// [`xinfo_stream_full`]: ./trait.StreamCommands.html#method.xinfo_stream_full
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoGroupFull {
+    pub name: String,
+    pub last_delivered_id: StreamEntryId,
+    /// Redis 7+. `None` when Redis can't tell how many entries were read.
+    pub entries_read: Option<usize>,
+    /// Redis 7+. `None` when Redis can't tell how far behind the group is.
+    pub lag: Option<usize>,
+    pub pel_count: usize,
+    pub pending: Vec<StreamInfoPendingEntry>,
+    pub consumers: Vec<StreamInfoConsumerFull>,
+    pub extra: HashMap<String, Value>,
+}
+
+/// A consumer parsed from [`xinfo_stream_full`] command.
+///
+/// [`xinfo_stream_full`]: ./trait.StreamCommands.html#method.xinfo_stream_full
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoConsumerFull {
+    pub name: String,
+    /// Unix time in ms of the last interaction.
+    pub seen_time: usize,
+    /// Redis 7.2+. Unix time in ms of the last successful interaction.
+    pub active_time: Option<i64>,
+    pub pel_count: usize,
+    /// The consumer's pending entries. `consumer` isn't set on these.
+    pub pending: Vec<StreamInfoPendingEntry>,
+    pub extra: HashMap<String, Value>,
+}
+
+/// A pending entry parsed from [`xinfo_stream_full`] command.
+///
+/// [`xinfo_stream_full`]: ./trait.StreamCommands.html#method.xinfo_stream_full
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoPendingEntry {
+    pub id: StreamEntryId,
+    pub consumer: String,
+    /// Unix time in ms of the last delivery.
+    pub delivery_time: usize,
+    pub delivery_count: usize,
 }
 
 /// Reply type used with [`xinfo_consumer`] command.
@@ -302,6 +1096,9 @@ pub struct StreamInfoConsumer {
     pub name: String,
     pub pending: usize,
     pub idle: usize,
+    /// Redis 7.2+. Ms since the last successful interaction, `-1` if never.
+    pub inactive: Option<i64>,
+    pub extra: HashMap<String, Value>,
 }
 
 /// A group parsed from [`xinfo_groups`] command.
@@ -313,13 +1110,18 @@ pub struct StreamInfoGroup {
     pub name: String,
     pub consumers: usize,
     pub pending: usize,
-    pub last_delivered_id: String,
+    pub last_delivered_id: StreamEntryId,
+    /// Redis 7+. `None` when Redis can't tell how many entries were read.
+    pub entries_read: Option<usize>,
+    /// Redis 7+. `None` when Redis can't tell how far behind the group is.
+    pub lag: Option<usize>,
+    pub extra: HashMap<String, Value>,
 }
 
 /// Represents a pending message parsed from `xpending` methods.
 #[derive(Default, Debug, Clone)]
 pub struct StreamPendingId {
-    pub id: String,
+    pub id: StreamEntryId,
     pub consumer: String,
     pub last_delivered_ms: usize,
     pub times_delivered: usize,
@@ -333,34 +1135,45 @@ pub struct StreamKey {
 }
 
 impl StreamKey {
-    pub fn just_ids(&self) -> Vec<&String> {
-        self.ids.iter().map(|msg| &msg.id).collect::<Vec<&String>>()
+    pub fn just_ids(&self) -> Vec<&StreamEntryId> {
+        self.ids
+            .iter()
+            .map(|msg| &msg.id)
+            .collect::<Vec<&StreamEntryId>>()
     }
 }
 
-/// Represents a stream `id` and its field/values as a `HashMap`
-#[derive(Default, Debug, Clone)]
+/// Represents a stream `id` and its field/values in the order they were added.
+///
+/// Redis allows the same field more than once per entry, so `fields` may
+/// contain duplicates. The lookup helpers return the first match.
+///
+/// Breaking change: `fields` replaces the old `map: HashMap<String, Value>`
+/// field. Use `get`, `find` and `contains_key` for lookups, or `to_map`
+/// for a `HashMap` with the old behaviour.
+///
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct StreamId {
-    pub id: String,
-    pub map: HashMap<String, Value>,
+    pub id: StreamEntryId,
+    pub fields: Vec<(String, Value)>,
 }
 
 impl StreamId {
+    /// Parses an `[id, [field, value, ...]]` entry,
+    /// or a bare id from `JUSTID` replies.
     pub fn from_bulk_value(v: &Value) -> RedisResult<Self> {
-        let mut stream_id = StreamId::default();
         match *v {
-            Value::Bulk(ref values) => {
-                if let Some(v) = values.get(0) {
-                    stream_id.id = from_redis_value(&v)?;
-                }
-                if let Some(v) = values.get(1) {
-                    stream_id.map = from_redis_value(&v)?;
-                }
+            Value::Bulk(_) => {
+                let (id, fields) = entry_parts(v)?;
+                Ok(StreamId { id, fields })
             }
-            _ => {}
+            // JUSTID replies only have the id
+            Value::Data(_) | Value::Status(_) => Ok(StreamId {
+                id: from_redis_value(v)?,
+                ..StreamId::default()
+            }),
+            _ => Err(unexpected("Invalid stream entry", ENTRY, v)),
         }
-
-        Ok(stream_id)
     }
 
     pub fn get<T: FromRedisValue>(&self, key: &str) -> Option<T> {
@@ -371,7 +1184,19 @@ impl StreamId {
     }
 
     pub fn find(&self, key: &&str) -> Option<&Value> {
-        self.map.get(*key)
+        self.fields
+            .iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns every value for a field that was added more than once.
+    pub fn find_all(&self, key: &str) -> Vec<&Value> {
+        self.fields
+            .iter()
+            .filter(|(field, _)| field == key)
+            .map(|(_, value)| value)
+            .collect()
     }
 
     pub fn contains_key(&self, key: &&str) -> bool {
@@ -379,61 +1204,405 @@ impl StreamId {
     }
 
     pub fn len(&self) -> usize {
-        self.map.len()
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Collect the field/values into a `HashMap`.
+    /// Duplicate fields keep the last value.
+    pub fn to_map(&self) -> HashMap<String, Value> {
+        self.fields.iter().cloned().collect()
+    }
+
+    /// Encode the field/values for `xadd_map`, keeping order and duplicates.
+    /// Errors if a value is `Nil` or a nested bulk reply.
+    ///
+    pub fn to_stream_fields(&self) -> RedisResult<Vec<(String, Vec<u8>)>> {
+        self.fields
+            .iter()
+            .map(|(field, value)| Ok((field.to_owned(), value_arg(field, value)?)))
+            .collect()
+    }
+}
+
+/// Binary-safe variant of [`StreamKey`].
+///
+/// [`StreamKey`]: ./struct.StreamKey.html
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamBytesKey {
+    pub key: Vec<u8>,
+    pub ids: Vec<StreamBytesId>,
+}
+
+impl StreamBytesKey {
+    pub fn just_ids(&self) -> Vec<&StreamEntryId> {
+        self.ids
+            .iter()
+            .map(|msg| &msg.id)
+            .collect::<Vec<&StreamEntryId>>()
+    }
+}
+
+/// Binary-safe variant of [`StreamId`].
+/// Field names are raw bytes and values are left as `Value`.
+///
+/// [`StreamId`]: ./struct.StreamId.html
+///
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct StreamBytesId {
+    pub id: StreamEntryId,
+    pub fields: Vec<(Vec<u8>, Value)>,
+}
+
+impl StreamBytesId {
+    /// Parses an `[id, [field, value, ...]]` entry.
+    pub fn from_bulk_value(v: &Value) -> RedisResult<Self> {
+        let (id, fields) = entry_parts(v)?;
+        Ok(StreamBytesId { id, fields })
+    }
+
+    pub fn get<T: FromRedisValue>(&self, key: &[u8]) -> Option<T> {
+        match self.find(key) {
+            Some(x) => from_redis_value(x).ok(),
+            None => None,
+        }
+    }
+
+    pub fn find(&self, key: &[u8]) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field.as_slice() == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns every value for a field that was added more than once.
+    pub fn find_all(&self, key: &[u8]) -> Vec<&Value> {
+        self.fields
+            .iter()
+            .filter(|(field, _)| field.as_slice() == key)
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.find(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Collect the field/values into a `HashMap`.
+    /// Duplicate fields keep the last value.
+    pub fn to_map(&self) -> HashMap<Vec<u8>, Value> {
+        self.fields.iter().cloned().collect()
+    }
+
+    /// Encode the field/values for `xadd_map`, keeping order and duplicates.
+    /// Errors if a value is `Nil` or a nested bulk reply.
+    ///
+    pub fn to_stream_fields(&self) -> RedisResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.fields
+            .iter()
+            .map(|(field, value)| {
+                let arg = value_arg(&String::from_utf8_lossy(field), value)?;
+                Ok((field.to_owned(), arg))
+            })
+            .collect()
+    }
+}
+
+// Entry values come back as bulk strings, so scalar replies are written
+// the way Redis would have returned them. `Nil` and nested bulk replies
+// have no argument form.
+fn value_arg(field: &str, value: &Value) -> RedisResult<Vec<u8>> {
+    match *value {
+        Value::Data(ref bytes) => Ok(bytes.to_owned()),
+        Value::Status(ref s) => Ok(s.as_bytes().to_vec()),
+        Value::Int(i) => Ok(i.to_string().into_bytes()),
+        Value::Okay => Ok(b"OK".to_vec()),
+        Value::Nil | Value::Bulk(_) => Err(RedisError::from((
+            redis::ErrorKind::TypeError,
+            "Stream field value can't be written as an argument",
+            format!("field `{}`: {}", field, describe(value)),
+        ))),
+    }
+}
+
+// Reply parsing helpers. Every parser checks the shape of the reply and
+// returns a `TypeError` describing what it expected and what it received.
+
+const ENTRY: &str = "[id, [field, value, ...]]";
+
+/// A short description of a reply value for error messages.
+fn describe(v: &Value) -> String {
+    match *v {
+        Value::Nil => "nil".to_string(),
+        Value::Int(i) => format!("integer {}", i),
+        Value::Data(ref bytes) => match from_utf8(bytes) {
+            Ok(s) if s.len() <= 64 => format!("bulk string {:?}", s),
+            Ok(_) => format!("bulk string of {} bytes", bytes.len()),
+            Err(_) => format!("binary data of {} bytes", bytes.len()),
+        },
+        Value::Bulk(ref values) => format!("array of {} values", values.len()),
+        Value::Status(ref s) => format!("status {:?}", s),
+        Value::Okay => "OK".to_string(),
+    }
+}
+
+fn unexpected(desc: &'static str, expected: &str, got: &Value) -> RedisError {
+    RedisError::from((
+        redis::ErrorKind::TypeError,
+        desc,
+        format!("expected {} but got {}", expected, describe(got)),
+    ))
+}
+
+fn bulk<'a>(v: &'a Value, desc: &'static str, expected: &str) -> RedisResult<&'a [Value]> {
+    match *v {
+        Value::Bulk(ref values) => Ok(values),
+        _ => Err(unexpected(desc, expected, v)),
+    }
+}
+
+fn bulk_len<'a>(
+    v: &'a Value,
+    len: usize,
+    desc: &'static str,
+    expected: &str,
+) -> RedisResult<&'a [Value]> {
+    match bulk(v, desc, expected)? {
+        values if values.len() == len => Ok(values),
+        _ => Err(unexpected(desc, expected, v)),
+    }
+}
+
+// Like `bulk` but nil is an empty array.
+fn bulk_or_nil<'a>(v: &'a Value, desc: &'static str, expected: &str) -> RedisResult<&'a [Value]> {
+    match *v {
+        Value::Nil => Ok(&[]),
+        _ => bulk(v, desc, expected),
+    }
+}
+
+fn parse<T: FromRedisValue>(v: &Value, desc: &'static str, expected: &str) -> RedisResult<T> {
+    from_redis_value(v).map_err(|_| unexpected(desc, expected, v))
+}
+
+// [field, value, field, value, ...]
+fn field_pairs<F: FromRedisValue>(v: &Value) -> RedisResult<Vec<(F, Value)>> {
+    let desc = "Invalid stream entry fields";
+    let expected = "[field, value, ...]";
+    let values = bulk_or_nil(v, desc, expected)?;
+    if values.len() % 2 != 0 {
+        return Err(unexpected(desc, "an even number of field/values", v));
+    }
+    values
+        .chunks(2)
+        .map(|pair| Ok((parse(&pair[0], desc, "a field name")?, pair[1].to_owned())))
+        .collect()
+}
+
+// [id, [field, value, ...]]
+fn entry_parts<F: FromRedisValue>(v: &Value) -> RedisResult<(StreamEntryId, Vec<(F, Value)>)> {
+    let parts = bulk_len(v, 2, "Invalid stream entry", ENTRY)?;
+    Ok((from_redis_value(&parts[0])?, field_pairs(&parts[1])?))
+}
+
+// [[id, [field, value, ...]], ...]
+fn stream_ids(v: &Value) -> RedisResult<Vec<StreamId>> {
+    bulk_or_nil(v, "Invalid stream entries", "an array of entries")?
+        .iter()
+        // deleted entries are returned as nil by xclaim
+        .filter(|entry| **entry != Value::Nil)
+        .map(StreamId::from_bulk_value)
+        .collect()
+}
+
+fn bytes_ids(v: &Value) -> RedisResult<Vec<StreamBytesId>> {
+    bulk_or_nil(v, "Invalid stream entries", "an array of entries")?
+        .iter()
+        .filter(|entry| **entry != Value::Nil)
+        .map(StreamBytesId::from_bulk_value)
+        .collect()
+}
+
+// [[key, [entry, ...]], ...]
+// Blocking reads return nil on timeout.
+fn stream_keys<K: FromRedisValue, T>(
+    v: &Value,
+    ids: fn(&Value) -> RedisResult<Vec<T>>,
+) -> RedisResult<Vec<(K, Vec<T>)>> {
+    let desc = "Invalid stream read reply";
+    bulk_or_nil(v, desc, "an array of [key, [entry, ...]]")?
+        .iter()
+        .map(|row| {
+            let parts = bulk_len(row, 2, desc, "[key, [entry, ...]]")?;
+            Ok((parse(&parts[0], desc, "a stream key")?, ids(&parts[1])?))
+        })
+        .collect()
+}
+
+// XINFO replies are flat [name, value, ...] arrays.
+fn info_map(v: &Value, desc: &'static str) -> RedisResult<HashMap<String, Value>> {
+    let values = bulk(v, desc, "[name, value, ...]")?;
+    if values.len() % 2 != 0 {
+        return Err(unexpected(desc, "an even number of name/values", v));
+    }
+    values
+        .chunks(2)
+        .map(|pair| Ok((parse(&pair[0], desc, "a field name")?, pair[1].to_owned())))
+        .collect()
+}
+
+// Fields are removed as they're parsed, so whatever is left over
+// ends up in the `extra` map. These fields exist in every Redis
+// version with streams, so a missing one is an error.
+fn info_field<T: FromRedisValue>(
+    map: &mut HashMap<String, Value>,
+    field: &str,
+    desc: &'static str,
+) -> RedisResult<T> {
+    match map.remove(field) {
+        Some(v) => parse(&v, desc, &format!("a valid `{}`", field)),
+        None => Err(RedisError::from((
+            redis::ErrorKind::TypeError,
+            desc,
+            format!("missing `{}`", field),
+        ))),
+    }
+}
+
+// Fields added in later Redis versions are `None` when missing.
+fn info_optional_field<T: FromRedisValue>(
+    map: &mut HashMap<String, Value>,
+    field: &str,
+    desc: &'static str,
+) -> RedisResult<Option<T>> {
+    match map.remove(field) {
+        Some(v) => parse(&v, desc, &format!("a valid `{}`", field)),
+        None => Ok(None),
+    }
+}
+
+fn info_entry(map: &mut HashMap<String, Value>, field: &str) -> RedisResult<StreamId> {
+    match map.remove(field) {
+        Some(Value::Nil) | None => Ok(StreamId::default()),
+        Some(v) => StreamId::from_bulk_value(&v),
+    }
+}
+
+// [[id, consumer, delivery-time, delivery-count], ...]
+// Consumer pending lists leave out the consumer name.
+fn info_pending(
+    map: &mut HashMap<String, Value>,
+    with_consumer: bool,
+    desc: &'static str,
+) -> RedisResult<Vec<StreamInfoPendingEntry>> {
+    let v = match map.remove("pending") {
+        Some(v) => v,
+        None => return Ok(vec![]),
+    };
+    let expected = if with_consumer {
+        "[id, consumer, delivery-time, delivery-count]"
+    } else {
+        "[id, delivery-time, delivery-count]"
+    };
+    let mut pending = vec![];
+    for row in bulk(&v, desc, "an array of pending entries")? {
+        let parts = bulk_len(row, if with_consumer { 4 } else { 3 }, desc, expected)?;
+        let (consumer, times) = if with_consumer {
+            (parse(&parts[1], desc, "a consumer name")?, &parts[2..])
+        } else {
+            (String::new(), &parts[1..])
+        };
+        pending.push(StreamInfoPendingEntry {
+            id: from_redis_value(&parts[0])?,
+            consumer,
+            delivery_time: parse(&times[0], desc, "a delivery time")?,
+            delivery_count: parse(&times[1], desc, "a delivery count")?,
+        });
+    }
+    Ok(pending)
+}
+
+impl FromRedisValue for StreamBytesReadReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        Ok(StreamBytesReadReply {
+            keys: stream_keys(v, bytes_ids)?
+                .into_iter()
+                .map(|(key, ids)| StreamBytesKey { key, ids })
+                .collect(),
+        })
+    }
+}
+
+impl FromRedisValue for StreamBytesRangeReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        Ok(StreamBytesRangeReply { ids: bytes_ids(v)? })
     }
 }
 
 impl FromRedisValue for StreamReadReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let rows: Vec<HashMap<String, Vec<HashMap<String, HashMap<String, Value>>>>> =
-            from_redis_value(v)?;
-        let mut reply = StreamReadReply::default();
-        for row in &rows {
-            for (key, entry) in row.iter() {
-                let mut k = StreamKey::default();
-                k.key = key.to_owned();
-                for id_row in entry {
-                    let mut i = StreamId::default();
-                    for (id, map) in id_row.iter() {
-                        i.id = id.to_owned();
-                        i.map = map.to_owned();
-                    }
-                    k.ids.push(i);
-                }
-                reply.keys.push(k);
-            }
-        }
-        Ok(reply)
+        Ok(StreamReadReply {
+            keys: stream_keys(v, stream_ids)?
+                .into_iter()
+                .map(|(key, ids)| StreamKey { key, ids })
+                .collect(),
+        })
     }
 }
 
 impl FromRedisValue for StreamRangeReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let rows: Vec<HashMap<String, HashMap<String, Value>>> = from_redis_value(v)?;
-        let mut reply = StreamRangeReply::default();
-        for row in &rows {
-            let mut i = StreamId::default();
-            for (id, map) in row.iter() {
-                i.id = id.to_owned();
-                i.map = map.to_owned();
-            }
-            reply.ids.push(i);
-        }
-        Ok(reply)
+        Ok(StreamRangeReply {
+            ids: stream_ids(v)?,
+        })
     }
 }
 
 impl FromRedisValue for StreamClaimReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let rows: Vec<HashMap<String, HashMap<String, Value>>> = from_redis_value(v)?;
-        let mut reply = StreamClaimReply::default();
-        for row in &rows {
-            let mut i = StreamId::default();
-            for (id, map) in row.iter() {
-                i.id = id.to_owned();
-                i.map = map.to_owned();
+        Ok(StreamClaimReply {
+            ids: stream_ids(v)?,
+        })
+    }
+}
+
+impl FromRedisValue for StreamAutoClaimReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let desc = "Invalid xautoclaim reply";
+        let expected = "[next-id, [entry, ...], [deleted-id, ...]]";
+        let parts = bulk(v, desc, expected)?;
+        if parts.len() != 2 && parts.len() != 3 {
+            return Err(unexpected(desc, expected, v));
+        }
+        let mut reply = StreamAutoClaimReply {
+            next_stream_id: from_redis_value(&parts[0])?,
+            ..StreamAutoClaimReply::default()
+        };
+        for entry in bulk(&parts[1], desc, "an array of entries")? {
+            match *entry {
+                // Redis 6.2 returns deleted messages as nil
+                Value::Bulk(ref values) if values.get(1) == Some(&Value::Nil) => {}
+                Value::Nil => {}
+                _ => reply.claimed.push(StreamId::from_bulk_value(entry)?),
             }
-            reply.ids.push(i);
+        }
+        if let Some(v) = parts.get(2) {
+            reply.deleted_ids = bulk(v, desc, "an array of deleted ids")?
+                .iter()
+                .map(from_redis_value)
+                .collect::<RedisResult<_>>()?;
         }
         Ok(reply)
     }
@@ -441,59 +1610,65 @@ impl FromRedisValue for StreamClaimReply {
 
 impl FromRedisValue for StreamPendingReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let parts: (usize, Option<String>, Option<String>, Vec<Vec<String>>) = from_redis_value(v)?;
-        let count = parts.0.to_owned() as usize;
+        let desc = "Invalid xpending reply";
+        let parts = bulk_len(
+            v,
+            4,
+            desc,
+            "[count, start-id, end-id, [[consumer, count], ...]]",
+        )?;
+        let count: usize = parse(&parts[0], desc, "a pending count")?;
 
         if count == 0 {
-            Ok(StreamPendingReply::Empty)
-        } else {
-            let mut result = StreamPendingData::default();
-
-            let start_id = match parts.1.to_owned() {
-                Some(start) => Ok(start),
-                None => Err(Error::new(
-                    ErrorKind::Other,
-                    "IllegalState: Non-zero pending expects start id",
-                )),
-            }?;
-
-            let end_id = match parts.2.to_owned() {
-                Some(end) => Ok(end),
-                None => Err(Error::new(
-                    ErrorKind::Other,
-                    "IllegalState: Non-zero pending expects end id",
-                )),
-            }?;
-
-            result.count = count;
-            result.start_id = start_id;
-            result.end_id = end_id;
-
-            for consumer in &parts.3 {
-                let mut info = StreamInfoConsumer::default();
-                info.name = consumer[0].to_owned();
-                if let Ok(v) = consumer[1].to_owned().parse::<usize>() {
-                    info.pending = v;
-                }
-                result.consumers.push(info);
-            }
+            return Ok(StreamPendingReply::Empty);
+        }
+
+        // a non-zero count always has a start and end id
+        let start_id = match parts[1] {
+            Value::Nil => return Err(unexpected(desc, "a start id", &parts[1])),
+            ref v => from_redis_value(v)?,
+        };
+        let end_id = match parts[2] {
+            Value::Nil => return Err(unexpected(desc, "an end id", &parts[2])),
+            ref v => from_redis_value(v)?,
+        };
 
-            Ok(StreamPendingReply::Data(result))
+        let mut result = StreamPendingData {
+            count,
+            start_id,
+            end_id,
+            consumers: vec![],
+        };
+        for consumer in bulk_or_nil(&parts[3], desc, "an array of consumers")? {
+            let pair = bulk_len(consumer, 2, desc, "[consumer, count]")?;
+            result.consumers.push(StreamInfoConsumer {
+                name: parse(&pair[0], desc, "a consumer name")?,
+                pending: parse(&pair[1], desc, "a consumer pending count")?,
+                ..StreamInfoConsumer::default()
+            });
         }
+
+        Ok(StreamPendingReply::Data(result))
     }
 }
 
 impl FromRedisValue for StreamPendingCountReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let parts: Vec<Vec<(String, String, usize, usize)>> = from_redis_value(v)?;
+        let desc = "Invalid xpending reply";
         let mut reply = StreamPendingCountReply::default();
-        for row in &parts {
-            let mut p = StreamPendingId::default();
-            p.id = row[0].0.to_owned();
-            p.consumer = row[0].1.to_owned();
-            p.last_delivered_ms = row[0].2.to_owned();
-            p.times_delivered = row[0].3.to_owned();
-            reply.ids.push(p);
+        for row in bulk(v, desc, "an array of pending ids")? {
+            let parts = bulk_len(
+                row,
+                4,
+                desc,
+                "[id, consumer, last-delivered-ms, times-delivered]",
+            )?;
+            reply.ids.push(StreamPendingId {
+                id: from_redis_value(&parts[0])?,
+                consumer: parse(&parts[1], desc, "a consumer name")?,
+                last_delivered_ms: parse(&parts[2], desc, "an idle time")?,
+                times_delivered: parse(&parts[3], desc, "a delivery count")?,
+            });
         }
         Ok(reply)
     }
@@ -501,46 +1676,109 @@ impl FromRedisValue for StreamPendingCountReply {
 
 impl FromRedisValue for StreamInfoStreamReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let map: HashMap<String, Value> = from_redis_value(v)?;
-        let mut reply = StreamInfoStreamReply::default();
-        if let Some(v) = &map.get("last-generated-id") {
-            reply.last_generated_id = from_redis_value(v)?;
-        }
-        if let Some(v) = &map.get("radix-tree-nodes") {
-            reply.radix_tree_keys = from_redis_value(v)?;
-        }
-        if let Some(v) = &map.get("groups") {
-            reply.groups = from_redis_value(v)?;
-        }
-        if let Some(v) = &map.get("length") {
-            reply.length = from_redis_value(v)?;
-        }
-        if let Some(v) = &map.get("first-entry") {
-            reply.first_entry = StreamId::from_bulk_value(v)?;
-        }
-        if let Some(v) = &map.get("last-entry") {
-            reply.last_entry = StreamId::from_bulk_value(v)?;
-        }
-        Ok(reply)
+        let desc = "Invalid xinfo stream reply";
+        let mut map = info_map(v, desc)?;
+        Ok(StreamInfoStreamReply {
+            length: info_field(&mut map, "length", desc)?,
+            last_generated_id: info_field(&mut map, "last-generated-id", desc)?,
+            radix_tree_keys: info_field(&mut map, "radix-tree-keys", desc)?,
+            radix_tree_nodes: info_field(&mut map, "radix-tree-nodes", desc)?,
+            groups: info_field(&mut map, "groups", desc)?,
+            first_entry: info_entry(&mut map, "first-entry")?,
+            last_entry: info_entry(&mut map, "last-entry")?,
+            max_deleted_entry_id: info_optional_field(&mut map, "max-deleted-entry-id", desc)?,
+            entries_added: info_optional_field(&mut map, "entries-added", desc)?,
+            recorded_first_entry_id: info_optional_field(
+                &mut map,
+                "recorded-first-entry-id",
+                desc,
+            )?,
+            extra: map,
+        })
     }
 }
 
+impl FromRedisValue for StreamInfoStreamFullReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let desc = "Invalid xinfo stream full reply";
+        let mut map = info_map(v, desc)?;
+        let entries = match map.remove("entries") {
+            Some(v) => stream_ids(&v)?,
+            None => vec![],
+        };
+        let groups = match map.remove("groups") {
+            Some(v) => bulk_or_nil(&v, desc, "an array of groups")?
+                .iter()
+                .map(|group| info_group_full(group, desc))
+                .collect::<RedisResult<_>>()?,
+            None => vec![],
+        };
+        Ok(StreamInfoStreamFullReply {
+            length: info_field(&mut map, "length", desc)?,
+            radix_tree_keys: info_field(&mut map, "radix-tree-keys", desc)?,
+            radix_tree_nodes: info_field(&mut map, "radix-tree-nodes", desc)?,
+            last_generated_id: info_field(&mut map, "last-generated-id", desc)?,
+            max_deleted_entry_id: info_optional_field(&mut map, "max-deleted-entry-id", desc)?,
+            entries_added: info_optional_field(&mut map, "entries-added", desc)?,
+            recorded_first_entry_id: info_optional_field(
+                &mut map,
+                "recorded-first-entry-id",
+                desc,
+            )?,
+            entries,
+            groups,
+            extra: map,
+        })
+    }
+}
+
+fn info_group_full(v: &Value, desc: &'static str) -> RedisResult<StreamInfoGroupFull> {
+    let mut map = info_map(v, desc)?;
+    let pending = info_pending(&mut map, true, desc)?;
+    let consumers = match map.remove("consumers") {
+        Some(v) => bulk_or_nil(&v, desc, "an array of consumers")?
+            .iter()
+            .map(|consumer| info_consumer_full(consumer, desc))
+            .collect::<RedisResult<_>>()?,
+        None => vec![],
+    };
+    Ok(StreamInfoGroupFull {
+        name: info_field(&mut map, "name", desc)?,
+        last_delivered_id: info_field(&mut map, "last-delivered-id", desc)?,
+        entries_read: info_optional_field(&mut map, "entries-read", desc)?,
+        lag: info_optional_field(&mut map, "lag", desc)?,
+        pel_count: info_field(&mut map, "pel-count", desc)?,
+        pending,
+        consumers,
+        extra: map,
+    })
+}
+
+fn info_consumer_full(v: &Value, desc: &'static str) -> RedisResult<StreamInfoConsumerFull> {
+    let mut map = info_map(v, desc)?;
+    Ok(StreamInfoConsumerFull {
+        name: info_field(&mut map, "name", desc)?,
+        seen_time: info_field(&mut map, "seen-time", desc)?,
+        active_time: info_optional_field(&mut map, "active-time", desc)?,
+        pel_count: info_field(&mut map, "pel-count", desc)?,
+        pending: info_pending(&mut map, false, desc)?,
+        extra: map,
+    })
+}
+
 impl FromRedisValue for StreamInfoConsumersReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let consumers: Vec<HashMap<String, Value>> = from_redis_value(v)?;
+        let desc = "Invalid xinfo consumers reply";
         let mut reply = StreamInfoConsumersReply::default();
-        for map in consumers {
-            let mut c = StreamInfoConsumer::default();
-            if let Some(v) = &map.get("name") {
-                c.name = from_redis_value(v)?;
-            }
-            if let Some(v) = &map.get("pending") {
-                c.pending = from_redis_value(v)?;
-            }
-            if let Some(v) = &map.get("idle") {
-                c.idle = from_redis_value(v)?;
-            }
-            reply.consumers.push(c);
+        for consumer in bulk(v, desc, "an array of consumers")? {
+            let mut map = info_map(consumer, desc)?;
+            reply.consumers.push(StreamInfoConsumer {
+                name: info_field(&mut map, "name", desc)?,
+                pending: info_field(&mut map, "pending", desc)?,
+                idle: info_field(&mut map, "idle", desc)?,
+                inactive: info_optional_field(&mut map, "inactive", desc)?,
+                extra: map,
+            });
         }
 
         Ok(reply)
@@ -549,23 +1787,19 @@ impl FromRedisValue for StreamInfoConsumersReply {
 
 impl FromRedisValue for StreamInfoGroupsReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
-        let groups: Vec<HashMap<String, Value>> = from_redis_value(v)?;
+        let desc = "Invalid xinfo groups reply";
         let mut reply = StreamInfoGroupsReply::default();
-        for map in groups {
-            let mut g = StreamInfoGroup::default();
-            if let Some(v) = &map.get("name") {
-                g.name = from_redis_value(v)?;
-            }
-            if let Some(v) = &map.get("pending") {
-                g.pending = from_redis_value(v)?;
-            }
-            if let Some(v) = &map.get("consumers") {
-                g.consumers = from_redis_value(v)?;
-            }
-            if let Some(v) = &map.get("last-delivered-id") {
-                g.last_delivered_id = from_redis_value(v)?;
-            }
-            reply.groups.push(g);
+        for group in bulk(v, desc, "an array of groups")? {
+            let mut map = info_map(group, desc)?;
+            reply.groups.push(StreamInfoGroup {
+                name: info_field(&mut map, "name", desc)?,
+                consumers: info_field(&mut map, "consumers", desc)?,
+                pending: info_field(&mut map, "pending", desc)?,
+                last_delivered_id: info_field(&mut map, "last-delivered-id", desc)?,
+                entries_read: info_optional_field(&mut map, "entries-read", desc)?,
+                lag: info_optional_field(&mut map, "lag", desc)?,
+                extra: map,
+            });
         }
         Ok(reply)
     }
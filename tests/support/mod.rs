@@ -1,13 +1,17 @@
 // This file is mostly a direct copy of this file in redis-rs.
-// all the async code has been removed for now...
 // https://github.com/mitsuhiko/redis-rs/blob/master/tests/support/mod.rs
 
 #![allow(dead_code)]
 
 extern crate net2;
 extern crate rand;
+extern crate tokio;
 
+use futures::Future;
 use redis;
+use redis::Value;
+
+use redis_streams::StreamId;
 
 use std::env;
 use std::fs;
@@ -156,7 +160,34 @@ impl TestContext {
         self.client.get_connection().unwrap()
     }
 
+    pub async fn async_connection(&self) -> redis::RedisResult<redis::aio::Connection> {
+        self.client.get_async_connection().await
+    }
+
     pub fn stop_server(&mut self) {
         self.server.stop();
     }
 }
+
+pub fn block_on_all<F>(f: F) -> F::Output
+where
+    F: Future,
+{
+    let mut runtime = tokio::runtime::Builder::new()
+        .basic_scheduler()
+        .enable_io()
+        .build()
+        .unwrap();
+    runtime.block_on(f)
+}
+
+/// Builds a `StreamId` with id `1000-0` from string field/value pairs.
+pub fn stream_id(fields: &[(&str, &str)]) -> StreamId {
+    StreamId {
+        id: "1000-0".parse().unwrap(),
+        fields: fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), Value::Data(value.as_bytes().to_vec())))
+            .collect(),
+    }
+}
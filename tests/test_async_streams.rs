@@ -0,0 +1,681 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::aio::Connection;
+use redis::{RedisError, RedisResult};
+
+use futures::StreamExt;
+
+use redis_streams::{
+    tail, AsyncStreamCommands, StreamAddAckOptions, StreamClaimOptions, StreamClaimReply,
+    StreamInfoConsumersReply, StreamInfoGroupsReply, StreamInfoStreamReply, StreamMaxlen,
+    StreamPendingCountReply, StreamPendingReply, StreamRangeReply, StreamReadOptions,
+    StreamReadReply,
+};
+
+use std::collections::BTreeMap;
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::support::*;
+
+mod support;
+
+async fn xadd(con: &mut Connection) -> RedisResult<()> {
+    let _: String = con
+        .xadd("k1", "1000-0", &[("hello", "world"), ("redis", "streams")])
+        .await?;
+    let _: String = con.xadd("k1", "1000-1", &[("hello", "world2")]).await?;
+    let _: String = con.xadd("k2", "2000-0", &[("hello", "world")]).await?;
+    let _: String = con.xadd("k2", "2000-1", &[("hello", "world2")]).await?;
+    Ok(())
+}
+
+async fn xadd_keyrange(con: &mut Connection, key: &str, start: i32, end: i32) -> RedisResult<()> {
+    for _i in start..end {
+        let _: String = con.xadd(key, "*", &[("h", "w")]).await?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_async_assorted_1() {
+    // Tests the following commands....
+    // xadd
+    // xadd_map
+    // xadd_maxlen
+    // xread
+    // xlen
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        xadd(&mut con).await?;
+
+        // smoke test that we get the same id back
+        let result: String = con.xadd("k0", "1000-0", &[("x", "y")]).await?;
+        assert_eq!(result, "1000-0");
+
+        // xread reply
+        let reply: StreamReadReply = con.xread(&["k1", "k2", "k3"], &["0", "0", "0"]).await?;
+
+        // verify reply contains 2 keys even though we asked for 3
+        assert_eq!(&reply.keys.len(), &2usize);
+
+        // verify first key & first id exist
+        assert_eq!(&reply.keys[0].key, "k1");
+        assert_eq!(&reply.keys[0].ids.len(), &2usize);
+        assert_eq!(&reply.keys[0].ids[0].id, "1000-0");
+
+        // lookup the key in StreamId map
+        let hello: Option<String> = reply.keys[0].ids[0].get("hello");
+        assert_eq!(hello, Some("world".to_string()));
+
+        // verify the second key was written
+        assert_eq!(&reply.keys[1].key, "k2");
+        assert_eq!(&reply.keys[1].ids.len(), &2usize);
+        assert_eq!(&reply.keys[1].ids[0].id, "2000-0");
+
+        // test xadd_map
+        let mut map: BTreeMap<&str, &str> = BTreeMap::new();
+        map.insert("ab", "cd");
+        map.insert("ef", "gh");
+        map.insert("ij", "kl");
+        let _: String = con.xadd_map("k3", "3000-0", map).await?;
+
+        let reply: StreamRangeReply = con.xrange_all("k3").await?;
+        assert_eq!(reply.ids[0].contains_key(&"ab"), true);
+        assert_eq!(reply.ids[0].contains_key(&"ef"), true);
+        assert_eq!(reply.ids[0].contains_key(&"ij"), true);
+
+        // add 100 things to k4
+        xadd_keyrange(&mut con, "k4", 0, 100).await?;
+
+        // test xlen.. should have 100 items
+        let result: usize = con.xlen("k4").await?;
+        assert_eq!(result, 100);
+
+        // test xadd_maxlen
+        let _: String = con
+            .xadd_maxlen("k4", StreamMaxlen::Equals(10), "*", &[("h", "w")])
+            .await?;
+        let result: usize = con.xlen("k4").await?;
+        assert_eq!(result, 10);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_assorted_2() {
+    // Tests the following commands....
+    // xinfo_stream
+    // xinfo_groups
+    // xinfo_consumer
+    // xgroup_create
+    // xgroup_create_mkstream
+    // xread_options
+    // xack
+    // xpending
+    // xpending_count
+    // xpending_consumer_count
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        xadd(&mut con).await?;
+
+        // no key exists...
+        let reply: RedisResult<StreamInfoStreamReply> = con.xinfo_stream("k10").await;
+        assert_eq!(reply.is_err(), true);
+
+        // redo the connection because the above error
+        con = ctx.async_connection().await?;
+
+        // key should exist
+        let reply: StreamInfoStreamReply = con.xinfo_stream("k1").await?;
+        assert_eq!(&reply.first_entry.id, "1000-0");
+        assert_eq!(&reply.last_entry.id, "1000-1");
+        assert_eq!(&reply.last_generated_id, "1000-1");
+
+        // xgroup create (existing stream)
+        let _: String = con.xgroup_create("k1", "g1", "$").await?;
+
+        // xinfo groups (existing stream)
+        let reply: StreamInfoGroupsReply = con.xinfo_groups("k1").await?;
+        assert_eq!(&reply.groups.len(), &1);
+        assert_eq!(&reply.groups[0].name, &"g1");
+
+        // test xgroup create w/ mkstream @ 0
+        let _: String = con.xgroup_create_mkstream("k99", "g99", "0").await?;
+
+        let reply: StreamInfoGroupsReply = con.xinfo_groups("k99").await?;
+        assert_eq!(&reply.groups.len(), &1);
+        assert_eq!(&reply.groups[0].name, &"g99");
+        assert_eq!(&reply.groups[0].last_delivered_id, &"0-0");
+
+        let _: String = con.xadd("k99", "1000-0", &[("a", "b"), ("c", "d")]).await?;
+        let _: String = con.xadd("k99", "1000-1", &[("e", "f"), ("g", "h")]).await?;
+
+        // test empty PEL
+        let empty_reply: StreamPendingReply = con.xpending("k99", "g99").await?;
+        assert_eq!(empty_reply.count(), 0);
+        if let StreamPendingReply::Empty = empty_reply {
+            // looks good
+        } else {
+            panic!("Expected StreamPendingReply::Empty but got Data");
+        }
+
+        let reply: StreamReadReply = con
+            .xread_options(
+                &["k99"],
+                &[">"],
+                StreamReadOptions::default().group("g99", "c99"),
+            )
+            .await?;
+        assert_eq!(reply.keys[0].ids.len(), 2);
+
+        // read xinfo consumers again, should have 2 messages for the c99 consumer
+        let reply: StreamInfoConsumersReply = con.xinfo_consumers("k99", "g99").await?;
+        assert_eq!(reply.consumers[0].pending, 2);
+
+        // ack one of these messages
+        let result: i32 = con.xack("k99", "g99", &["1000-0"]).await?;
+        assert_eq!(result, 1);
+
+        // get pending messages already seen by this client
+        let reply: StreamReadReply = con
+            .xread_options(
+                &["k99"],
+                &["0"],
+                StreamReadOptions::default().group("g99", "c99"),
+            )
+            .await?;
+        assert_eq!(reply.keys.len(), 1);
+
+        // we should also have one pending here...
+        let reply: StreamInfoConsumersReply = con.xinfo_consumers("k99", "g99").await?;
+        assert_eq!(reply.consumers[0].pending, 1);
+
+        // add more and read so we can test xpending
+        let _: String = con.xadd("k99", "1001-0", &[("i", "j"), ("k", "l")]).await?;
+        let _: String = con.xadd("k99", "1001-1", &[("m", "n"), ("o", "p")]).await?;
+        let _: StreamReadReply = con
+            .xread_options(
+                &["k99"],
+                &[">"],
+                StreamReadOptions::default().group("g99", "c99"),
+            )
+            .await?;
+
+        let data_reply: StreamPendingReply = con.xpending("k99", "g99").await?;
+        assert_eq!(data_reply.count(), 3);
+
+        if let StreamPendingReply::Data(data) = data_reply {
+            assert_eq!(data.start_id, "1000-1");
+            assert_eq!(data.end_id, "1001-1");
+            assert_eq!(data.consumers.len(), 1);
+            assert_eq!(data.consumers[0].name, "c99");
+        } else {
+            panic!("Expected StreamPendingReply::Data but got Empty");
+        }
+
+        // both count variations have the same reply types
+        let reply: StreamPendingCountReply = con.xpending_count("k99", "g99", "-", "+", 10).await?;
+        assert_eq!(reply.ids.len(), 3);
+
+        let reply: StreamPendingCountReply = con
+            .xpending_consumer_count("k99", "g99", "-", "+", 10, "c99")
+            .await?;
+        assert_eq!(reply.ids.len(), 3);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xadd_maxlen_map() {
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        for i in 0..10 {
+            let mut map: BTreeMap<&str, &str> = BTreeMap::new();
+            let idx = i.to_string();
+            map.insert("idx", &idx);
+            let _: String = con
+                .xadd_maxlen_map("maxlen_map", StreamMaxlen::Equals(3), "*", map)
+                .await?;
+        }
+
+        let result: usize = con.xlen("maxlen_map").await?;
+        assert_eq!(result, 3);
+        let reply: StreamRangeReply = con.xrange_all("maxlen_map").await?;
+
+        assert_eq!(reply.ids[0].get("idx"), Some("7".to_string()));
+        assert_eq!(reply.ids[1].get("idx"), Some("8".to_string()));
+        assert_eq!(reply.ids[2].get("idx"), Some("9".to_string()));
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xclaim() {
+    // Tests the following commands....
+    // xclaim
+    // xclaim_options
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        // create the group
+        let _: String = con.xgroup_create_mkstream("k1", "g1", "$").await?;
+
+        // add some keys
+        xadd_keyrange(&mut con, "k1", 0, 10).await?;
+
+        // read the pending items for this key & group
+        let reply: StreamReadReply = con
+            .xread_options(
+                &["k1"],
+                &[">"],
+                StreamReadOptions::default().group("g1", "c1"),
+            )
+            .await?;
+        // verify we have 10 ids
+        assert_eq!(reply.keys[0].ids.len(), 10);
+
+        // save this StreamId for later
+        let claim = &reply.keys[0].ids[0];
+        let claim_justids = &reply.keys[0].just_ids();
+
+        // sleep for 5ms
+        sleep(Duration::from_millis(5));
+
+        // grab this id if > 4ms
+        let reply: StreamClaimReply = con.xclaim("k1", "g1", "c2", 4, &[claim.id]).await?;
+        assert_eq!(reply.ids.len(), 1);
+        assert_eq!(reply.ids[0].id, claim.id);
+
+        // we should 9 in c1 and 1 in c2
+        let reply: StreamPendingReply = con.xpending("k1", "g1").await?;
+        if let StreamPendingReply::Data(data) = reply {
+            assert_eq!(data.consumers[0].name, "c1");
+            assert_eq!(data.consumers[0].pending, 9);
+            assert_eq!(data.consumers[1].name, "c2");
+            assert_eq!(data.consumers[1].pending, 1);
+        }
+
+        // sleep for 5ms
+        sleep(Duration::from_millis(5));
+
+        // call force on the same claim.id
+        let _: StreamClaimReply = con
+            .xclaim_options(
+                "k1",
+                "g1",
+                "c3",
+                4,
+                &[claim.id],
+                StreamClaimOptions::default().with_force(),
+            )
+            .await?;
+
+        let reply: StreamPendingReply = con.xpending("k1", "g1").await?;
+        // we should have 9 w/ c1 and 1 w/ c3 now
+        if let StreamPendingReply::Data(data) = reply {
+            assert_eq!(data.consumers[1].name, "c3");
+            assert_eq!(data.consumers[1].pending, 1);
+        }
+
+        // sleep for 5ms
+        sleep(Duration::from_millis(5));
+
+        // claim and only return JUSTID
+        let claimed: Vec<String> = con
+            .xclaim_options(
+                "k1",
+                "g1",
+                "c5",
+                4,
+                &claim_justids,
+                StreamClaimOptions::default().with_force().with_justid(),
+            )
+            .await?;
+        assert_eq!(claimed.len(), 10);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xdel() {
+    // Tests the following commands....
+    // xdel
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        // add some keys
+        xadd(&mut con).await?;
+
+        // delete the first stream item for this key
+        let result: i32 = con.xdel("k1", &["1000-0"]).await?;
+        assert_eq!(result, 1);
+
+        let result: i32 = con.xdel("k2", &["2000-0", "2000-1", "2000-2"]).await?;
+        // should equal 2 since the last id doesn't exist
+        assert_eq!(result, 2);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xtrim() {
+    // Tests the following commands....
+    // xtrim
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        // add some keys
+        xadd_keyrange(&mut con, "k1", 0, 100).await?;
+
+        // trim key to 50
+        let result: i32 = con.xtrim("k1", StreamMaxlen::Equals(50)).await?;
+        assert_eq!(result, 50);
+        // we should end up with 40 after this call
+        let result: i32 = con.xtrim("k1", StreamMaxlen::Equals(10)).await?;
+        assert_eq!(result, 40);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xgroup() {
+    // Tests the following commands....
+    // xgroup_create_mkstream
+    // xgroup_setid
+    // xgroup_destroy
+    // xgroup_delconsumer
+    // xgroup_create_if_missing
+    // xgroup_create_mkstream_if_missing
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        // test xgroup create w/ mkstream @ 0
+        let _: String = con.xgroup_create_mkstream("k1", "g1", "0").await?;
+
+        // destroy this new stream group
+        let result: i32 = con.xgroup_destroy("k1", "g1").await?;
+        assert_eq!(result, 1);
+
+        // add some keys
+        xadd(&mut con).await?;
+
+        // create the group again using an existing stream
+        let _: String = con.xgroup_create("k1", "g1", "$").await?;
+
+        // rewind the group so we can read the existing messages
+        let _: String = con.xgroup_setid("k1", "g1", "0").await?;
+
+        // read from the group so we can register the consumer
+        let reply: StreamReadReply = con
+            .xread_options(
+                &["k1"],
+                &[">"],
+                StreamReadOptions::default().group("g1", "c1"),
+            )
+            .await?;
+        assert_eq!(reply.keys[0].ids.len(), 2);
+
+        let result: i32 = con.xgroup_delconsumer("k1", "g1", "c1").await?;
+        // returns the number of pending message this client had open
+        assert_eq!(result, 2);
+
+        let result: i32 = con.xgroup_destroy("k1", "g1").await?;
+        assert_eq!(result, 1);
+
+        // idempotent group creation
+        assert!(con.xgroup_create_if_missing("k1", "g1", "$").await?);
+        assert!(!con.xgroup_create_if_missing("k1", "g1", "$").await?);
+        assert!(
+            con.xgroup_create_mkstream_if_missing("k2", "g1", "0")
+                .await?
+        );
+        assert!(
+            !con.xgroup_create_mkstream_if_missing("k2", "g1", "0")
+                .await?
+        );
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xrange() {
+    // Tests the following commands....
+    // xrange (-/+ variations)
+    // xrange_all
+    // xrange_count
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        xadd(&mut con).await?;
+
+        let reply: StreamRangeReply = con.xrange_all("k1").await?;
+        assert_eq!(reply.ids.len(), 2);
+
+        let reply: StreamRangeReply = con.xrange("k1", "1000-1", "+").await?;
+        assert_eq!(reply.ids.len(), 1);
+
+        let reply: StreamRangeReply = con.xrange("k1", "-", "1000-0").await?;
+        assert_eq!(reply.ids.len(), 1);
+
+        let reply: StreamRangeReply = con.xrange_count("k1", "-", "+", 1).await?;
+        assert_eq!(reply.ids.len(), 1);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xrevrange() {
+    // Tests the following commands....
+    // xrevrange (+/- variations)
+    // xrevrange_all
+    // xrevrange_count
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        xadd(&mut con).await?;
+
+        let reply: StreamRangeReply = con.xrevrange_all("k1").await?;
+        assert_eq!(reply.ids.len(), 2);
+
+        let reply: StreamRangeReply = con.xrevrange("k1", "1000-1", "-").await?;
+        assert_eq!(reply.ids.len(), 2);
+
+        let reply: StreamRangeReply = con.xrevrange("k1", "+", "1000-1").await?;
+        assert_eq!(reply.ids.len(), 1);
+
+        let reply: StreamRangeReply = con.xrevrange_count("k1", "+", "-", 1).await?;
+        assert_eq!(reply.ids.len(), 1);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_xadd_xack() {
+    // Tests the following commands....
+    // xadd_xack
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        let _: String = con.xgroup_create_mkstream("in", "g1", "0").await?;
+        let _: String = con.xadd("in", "1000-0", &[("n", "1")]).await?;
+        let _: StreamReadReply = con
+            .xread_options(
+                &["in"],
+                &[">"],
+                StreamReadOptions::default().group("g1", "c1"),
+            )
+            .await?;
+
+        let id = con
+            .xadd_xack(
+                "out",
+                &[("total", "1")],
+                "in",
+                "g1",
+                "1000-0",
+                StreamAddAckOptions::default(),
+            )
+            .await?;
+        assert!(id.is_some());
+
+        let reply: StreamPendingReply = con.xpending("in", "g1").await?;
+        assert_eq!(reply.count(), 0);
+
+        let id = con
+            .xadd_xack(
+                "out",
+                &[("total", "1")],
+                "in",
+                "g1",
+                "1000-0",
+                StreamAddAckOptions::default(),
+            )
+            .await?;
+        assert_eq!(id, None);
+
+        let len: usize = con.xlen("out").await?;
+        assert_eq!(len, 1);
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_tail() {
+    // Tests the following....
+    // tail follows each key from its start id
+    // $ only returns new messages
+    // tail reconnects and resumes after the connection is killed
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        xadd(&mut con).await?;
+
+        let opts = StreamReadOptions::default().block(100);
+        let mut messages = Box::pin(tail(ctx.client.clone(), &["k1", "k2"], &["0", "$"], opts));
+
+        let (key, msg) = messages.next().await.unwrap()?;
+        assert_eq!(key, "k1");
+        assert_eq!(msg.id, "1000-0");
+        let (key, msg) = messages.next().await.unwrap()?;
+        assert_eq!(key, "k1");
+        assert_eq!(msg.id, "1000-1");
+
+        let _: String = con.xadd("k2", "2000-2", &[("hello", "world3")]).await?;
+        let (key, msg) = messages.next().await.unwrap()?;
+        assert_eq!(key, "k2");
+        assert_eq!(msg.id, "2000-2");
+
+        // kill the tail's connection
+        let _: () = redis::cmd("CLIENT")
+            .arg("KILL")
+            .arg("TYPE")
+            .arg("normal")
+            .arg("SKIPME")
+            .arg("yes")
+            .query_async(&mut con)
+            .await?;
+        let _: String = con.xadd("k1", "1000-2", &[("hello", "world3")]).await?;
+
+        // the connection error may be yielded before resuming
+        let (key, msg) = loop {
+            if let Ok(item) = messages.next().await.unwrap() {
+                break item;
+            }
+        };
+        assert_eq!(key, "k1");
+        assert_eq!(msg.id, "1000-2");
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_tail_errors() {
+    // Tests the following....
+    // mismatched keys and ids end the stream after one error
+    // errors other than a dropped connection end the stream
+
+    let ctx = TestContext::new();
+    block_on_all(async move {
+        let mut con = ctx.async_connection().await?;
+
+        let opts = StreamReadOptions::default().block(100);
+        let mut messages = Box::pin(tail(ctx.client.clone(), &["k1", "k2"], &["0"], opts));
+        assert!(messages.next().await.unwrap().is_err());
+        assert!(messages.next().await.is_none());
+
+        let _: () = redis::cmd("SET")
+            .arg("k3")
+            .arg("v")
+            .query_async(&mut con)
+            .await?;
+        let opts = StreamReadOptions::default().block(100);
+        let mut messages = Box::pin(tail(ctx.client.clone(), &["k3"], &["0"], opts));
+        assert!(messages.next().await.unwrap().is_err());
+        assert!(messages.next().await.is_none());
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_async_tail_retry() {
+    // Tests the following....
+    // failed connects are yielded and retried instead of ending the stream
+
+    block_on_all(async move {
+        // nothing listens on port 1
+        let client = redis::Client::open("redis://127.0.0.1:1/").unwrap();
+        let opts = StreamReadOptions::default().block(100);
+        let mut messages = Box::pin(tail(client, &["k1"], &["0"], opts));
+        for _ in 0..3 {
+            let err = messages.next().await.unwrap().unwrap_err();
+            assert!(err.is_connection_refusal() || err.is_io_error());
+        }
+    });
+}
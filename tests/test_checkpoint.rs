@@ -0,0 +1,74 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{
+    StreamCheckpointReader, StreamCheckpointStore, StreamCommands, StreamRangeReply,
+};
+
+use std::time::Duration;
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+fn test_checkpoint() {
+    // Tests the following....
+    // a new reader starts from start_id
+    // processed ids are saved and a restarted reader resumes after them
+    // unsaved progress is read again after a restart
+    // processed_with_xadd saves the checkpoint with the output message
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    for i in 0..3 {
+        let _: RedisResult<String> = con.xadd("k1", format!("1000-{}", i), &[("idx", i)]);
+    }
+    let _: RedisResult<String> = con.xadd("k2", "2000-0", &[("idx", 0)]);
+
+    let store = StreamCheckpointStore::new("checkpoints:r1");
+    let mut reader = StreamCheckpointReader::start(&mut con, store.clone(), &["k1", "k2"], "0")
+        .unwrap()
+        .interval(Duration::from_secs(60));
+
+    let reply = reader.read(&mut con).unwrap();
+    assert_eq!(reply.keys.len(), 2);
+    assert_eq!(reader.cursor().ids(), vec!["1000-2", "2000-0"]);
+
+    reader.processed("k1", "1000-0");
+    reader.save(&mut con).unwrap();
+    // only k2 is unsaved here
+    reader.processed("k2", "2000-0");
+
+    let saved = store.load(&mut con).unwrap();
+    assert_eq!(saved.keys(), vec!["k1"]);
+    assert_eq!(saved.get("k1"), Some("1000-0"));
+
+    // restart
+    let mut reader = StreamCheckpointReader::start(&mut con, store.clone(), &["k1", "k2"], "0")
+        .unwrap()
+        .interval(Duration::from_secs(0));
+    assert_eq!(reader.cursor().ids(), vec!["1000-0", "0"]);
+
+    let reply = reader.read(&mut con).unwrap();
+    assert_eq!(reply.keys[0].ids.len(), 2);
+    assert_eq!(reply.keys[1].ids.len(), 1);
+
+    let id = reader
+        .processed_with_xadd(&mut con, "k1", "1000-2", "out", &[("idx", 2)])
+        .unwrap();
+    let reply: StreamRangeReply = con.xrange_all("out").unwrap();
+    assert_eq!(reply.ids[0].id, id.as_str());
+
+    let saved = store.load(&mut con).unwrap();
+    assert_eq!(saved.get("k1"), Some("1000-2"));
+
+    // the interval has passed, so read saves the marked ids first
+    reader.processed("k2", "2000-0");
+    let _ = reader.read(&mut con).unwrap();
+    let saved = store.load(&mut con).unwrap();
+    assert_eq!(saved.get("k2"), Some("2000-0"));
+}
@@ -0,0 +1,82 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{StreamCommands, StreamConsumer, StreamPendingReply, StreamReadOptions};
+
+use std::sync::{Arc, Mutex};
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+fn test_stream_consumer() {
+    // Tests the following....
+    // group creation w/ an existing group (BUSYGROUP)
+    // failed messages stay pending and are passed to on_error
+    // pending messages are drained on the next run
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    // create the group up front so the consumer has to tolerate BUSYGROUP
+    let result: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "0");
+    assert_eq!(result.is_ok(), true);
+
+    for i in 0..3 {
+        let idx = i.to_string();
+        let _: RedisResult<String> = con.xadd("k1", "*", &[("idx", &idx)]);
+    }
+
+    // fail the second message and stop once all three are seen
+    let mut seen = 0;
+    let errors = Arc::new(Mutex::new(vec![]));
+    let on_error = errors.clone();
+    let mut consumer = StreamConsumer::new(ctx.connection(), &["k1"], "g1", "c1", |_, msg| {
+        seen += 1;
+        if msg.get("idx") == Some("1".to_string()) {
+            Err("failed")
+        } else {
+            Ok(())
+        }
+    })
+    .read_options(StreamReadOptions::default().block(10).count(10))
+    .on_error(move |key, msg, err| {
+        let idx: String = msg.get("idx").unwrap();
+        on_error.lock().unwrap().push((key.to_string(), idx, err));
+    });
+
+    consumer.ensure_groups().unwrap();
+    assert_eq!(consumer.drain_pending(), Ok(0));
+    assert_eq!(consumer.read_once(), Ok(3));
+    drop(consumer);
+    assert_eq!(seen, 3);
+    assert_eq!(
+        *errors.lock().unwrap(),
+        vec![("k1".to_string(), "1".to_string(), "failed")]
+    );
+
+    // only the failed message should be left pending
+    let reply: StreamPendingReply = con.xpending("k1", "g1").unwrap();
+    assert_eq!(reply.count(), 1);
+
+    // run again: the pending message is retried first and the
+    // shutdown handle stops the loop after the next read
+    let mut retried = vec![];
+    let mut consumer = StreamConsumer::new(ctx.connection(), &["k1"], "g1", "c1", |_, msg| {
+        retried.push(msg.get::<String>("idx").unwrap());
+        Ok::<(), String>(())
+    })
+    .read_options(StreamReadOptions::default().block(10));
+
+    let shutdown = consumer.shutdown_handle();
+    shutdown.shutdown();
+    consumer.run().unwrap();
+    drop(consumer);
+    assert_eq!(retried, vec!["1".to_string()]);
+
+    let reply: StreamPendingReply = con.xpending("k1", "g1").unwrap();
+    assert_eq!(reply.count(), 0);
+}
@@ -0,0 +1,97 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{
+    StreamCommands, StreamCursor, StreamId, StreamKey, StreamReadOptions, StreamReadReply,
+};
+
+use crate::support::*;
+
+mod support;
+
+fn reply(key: &str, ids: &[&str]) -> StreamReadReply {
+    let ids = ids
+        .iter()
+        .map(|id| StreamId {
+            id: id.parse().unwrap(),
+            fields: vec![],
+        })
+        .collect();
+    StreamReadReply {
+        keys: vec![StreamKey {
+            key: key.to_string(),
+            ids,
+        }],
+    }
+}
+
+#[test]
+fn test_cursor_update() {
+    let mut cursor = StreamCursor::new();
+    cursor.insert("k2", "0");
+    cursor.insert("k1", "$");
+    cursor.insert("k3", ">");
+    assert_eq!(cursor.keys(), vec!["k1", "k2", "k3"]);
+    assert_eq!(cursor.ids(), vec!["$", "0", ">"]);
+
+    cursor.update(&reply("k2", &["1000-0", "1000-1"]));
+    cursor.update(&reply("k3", &["1000-0"]));
+    cursor.update(&reply("k4", &["1000-0"]));
+    assert_eq!(cursor.ids(), vec!["$", "1000-1", ">"]);
+    assert_eq!(cursor.get("k4"), None);
+
+    assert_eq!(cursor.remove("k1"), Some("$".to_string()));
+    assert_eq!(cursor.keys(), vec!["k2", "k3"]);
+    assert_eq!(cursor.len(), 2);
+}
+
+#[test]
+fn test_cursor() {
+    // Tests the following....
+    // read continues each key from its last id
+    // $ is resolved so messages added between reads aren't missed
+    // save and load round trip through a hash
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xadd("k1", "1000-0", &[("idx", 0)]);
+    let _: RedisResult<String> = con.xadd("k2", "2000-0", &[("idx", 0)]);
+
+    let mut cursor = StreamCursor::new();
+    assert_eq!(
+        cursor
+            .read(&mut con, StreamReadOptions::default())
+            .unwrap()
+            .keys
+            .len(),
+        0
+    );
+
+    cursor.insert("k1", "0");
+    cursor.insert("k2", "$");
+    let reply = cursor.read(&mut con, StreamReadOptions::default()).unwrap();
+    assert_eq!(reply.keys.len(), 1);
+    assert_eq!(reply.keys[0].key, "k1");
+    assert_eq!(cursor.ids(), vec!["1000-0", "2000-0"]);
+
+    let _: RedisResult<String> = con.xadd("k2", "2000-1", &[("idx", 1)]);
+    let reply = cursor.read(&mut con, StreamReadOptions::default()).unwrap();
+    assert_eq!(reply.keys.len(), 1);
+    assert_eq!(reply.keys[0].ids[0].id, "2000-1");
+
+    cursor.save(&mut con, "cursor").unwrap();
+    let loaded = StreamCursor::load(&mut con, "cursor").unwrap();
+    assert_eq!(loaded, cursor);
+
+    // removed keys are dropped from the hash
+    cursor.remove("k1");
+    cursor.save(&mut con, "cursor").unwrap();
+    let loaded = StreamCursor::load(&mut con, "cursor").unwrap();
+    assert_eq!(loaded.keys(), vec!["k2"]);
+
+    let loaded = StreamCursor::load(&mut con, "missing").unwrap();
+    assert!(loaded.is_empty());
+}
@@ -0,0 +1,93 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{
+    StreamClaimReply, StreamCommands, StreamDeadLetter, StreamPendingCountReply, StreamRangeReply,
+    StreamReadOptions, StreamReadReply,
+};
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+fn test_dead_letter() {
+    // Tests the following....
+    // messages over max_deliveries are copied with metadata and acked
+    // other pending messages are left alone
+    // redrive pushes messages back to the source stream
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "0");
+    for i in 0..3 {
+        let _: RedisResult<String> = con.xadd("k1", format!("1000-{}", i), &[("idx", i)]);
+    }
+    let _: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+
+    // each xclaim counts as another delivery
+    for _ in 0..2 {
+        let _: StreamClaimReply = con.xclaim("k1", "g1", "c2", 0, &["1000-1"]).unwrap();
+    }
+
+    // a small page size makes route follow the pending entries list
+    let dead_letter = StreamDeadLetter::new("k1", "g1", "k1:dlq")
+        .max_deliveries(2)
+        .count(1);
+    assert_eq!(dead_letter.route(&mut con), Ok(1));
+
+    let reply: StreamPendingCountReply = con.xpending_count("k1", "g1", "-", "+", 10).unwrap();
+    assert_eq!(reply.ids.len(), 2);
+    assert!(reply.ids.iter().all(|pending| pending.id != "1000-1"));
+
+    let reply: StreamRangeReply = con.xrange_all("k1:dlq").unwrap();
+    assert_eq!(reply.ids.len(), 1);
+    let message = &reply.ids[0];
+    assert_eq!(message.get("idx"), Some(1));
+    assert_eq!(
+        message.get(StreamDeadLetter::KEY_FIELD),
+        Some("k1".to_string())
+    );
+    assert_eq!(
+        message.get(StreamDeadLetter::ID_FIELD),
+        Some("1000-1".to_string())
+    );
+    assert_eq!(
+        message.get(StreamDeadLetter::CONSUMER_FIELD),
+        Some("c2".to_string())
+    );
+    assert_eq!(message.get(StreamDeadLetter::DELIVERIES_FIELD), Some(3));
+
+    // nothing else is over the limit
+    assert_eq!(dead_letter.route(&mut con), Ok(0));
+
+    // redrive adds the message back without the metadata
+    assert_eq!(dead_letter.redrive(&mut con, 10), Ok(1));
+    let len: usize = con.xlen("k1:dlq").unwrap();
+    assert_eq!(len, 0);
+
+    let reply: StreamRangeReply = con.xrevrange_count("k1", "+", "-", 1).unwrap();
+    let message = &reply.ids[0];
+    assert!(message.id > "1000-2".parse().unwrap());
+    assert_eq!(message.get("idx"), Some(1));
+    assert_eq!(message.len(), 1);
+
+    // the redriven message is delivered to the group again
+    let reply: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+    assert_eq!(reply.keys[0].ids.len(), 1);
+}
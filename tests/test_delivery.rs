@@ -0,0 +1,119 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{
+    Delivery, DeliveryDropBehavior, StreamCommands, StreamDeadLetter, StreamId,
+    StreamPendingCountReply, StreamRangeReply, StreamReadOptions, StreamReadReply,
+};
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "unsettled delivery dropped")]
+fn test_delivery_drop_panic() {
+    let delivery = Delivery::new("k1", "g1", "c1", StreamId::default());
+    let _ = delivery.on_drop(DeliveryDropBehavior::Panic);
+}
+
+#[test]
+fn test_delivery_drop_callback() {
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    fn on_drop(warning: &str) {
+        assert!(warning.contains("unsettled delivery dropped"));
+        assert!(warning.contains("key=k1"));
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    let delivery = Delivery::new("k1", "g1", "c1", StreamId::default())
+        .on_drop(DeliveryDropBehavior::Callback(on_drop));
+    drop(delivery);
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+
+    let delivery = Delivery::new("k1", "g1", "c1", StreamId::default())
+        .on_drop(DeliveryDropBehavior::Callback(on_drop));
+    delivery.nack();
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_delivery_drop_settled() {
+    let delivery =
+        Delivery::new("k1", "g1", "c1", StreamId::default()).on_drop(DeliveryDropBehavior::Panic);
+    delivery.nack();
+
+    let delivery =
+        Delivery::new("k1", "g1", "c1", StreamId::default()).on_drop(DeliveryDropBehavior::Panic);
+    let message = delivery.defer();
+    assert_eq!(message, StreamId::default());
+
+    let delivery =
+        Delivery::new("k1", "g1", "c1", StreamId::default()).on_drop(DeliveryDropBehavior::Ignore);
+    drop(delivery);
+}
+
+#[test]
+fn test_delivery() {
+    // Tests the following....
+    // ack removes the message from the pending entries list
+    // nack and defer leave it pending
+    // dead_letter moves it to the dead-letter stream
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "0");
+    for i in 0..4 {
+        let _: RedisResult<String> = con.xadd("k1", format!("1000-{}", i), &[("idx", i)]);
+    }
+    let reply: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+
+    let mut deliveries = Delivery::from_reply(reply, "g1", "c1").into_iter();
+    let delivery = deliveries.next().unwrap();
+    assert_eq!(delivery.key(), "k1");
+    assert_eq!(delivery.group(), "g1");
+    assert_eq!(delivery.consumer(), "c1");
+    assert_eq!(delivery.id(), "1000-0");
+    assert_eq!(delivery.message().get("idx"), Some(0));
+    assert_eq!(delivery.ack(&mut con), Ok(true));
+
+    deliveries.next().unwrap().nack();
+    let message = deliveries.next().unwrap().defer();
+    assert_eq!(message.id, "1000-2");
+
+    let dead_letter = StreamDeadLetter::new("k1", "g1", "k1:dlq");
+    let delivery = deliveries.next().unwrap();
+    assert_eq!(delivery.dead_letter(&mut con, &dead_letter), Ok(true));
+
+    let reply: StreamPendingCountReply = con.xpending_count("k1", "g1", "-", "+", 10).unwrap();
+    let ids: Vec<String> = reply.ids.iter().map(|p| p.id.to_string()).collect();
+    assert_eq!(ids, vec!["1000-1", "1000-2"]);
+
+    let reply: StreamRangeReply = con.xrange_all("k1:dlq").unwrap();
+    assert_eq!(reply.ids.len(), 1);
+    assert_eq!(
+        reply.ids[0].get(StreamDeadLetter::ID_FIELD),
+        Some("1000-3".to_string())
+    );
+
+    // acking twice reports the message was no longer pending
+    let message = StreamId {
+        id: "1000-0".parse().unwrap(),
+        fields: vec![],
+    };
+    let delivery = Delivery::new("k1", "g1", "c1", message);
+    assert_eq!(delivery.ack(&mut con), Ok(false));
+}
@@ -0,0 +1,248 @@
+#![cfg(feature = "derive")]
+
+extern crate redis;
+extern crate redis_streams;
+
+use redis::{RedisResult, Value};
+
+use redis_streams::{StreamCommands, StreamId, StreamMessage, StreamRangeReply};
+
+use crate::support::*;
+
+mod support;
+
+#[derive(StreamMessage, Debug, Clone, PartialEq)]
+struct Event {
+    #[stream(rename = "type")]
+    kind: String,
+    count: u32,
+    active: bool,
+    note: Option<String>,
+    #[stream(default)]
+    retries: u8,
+    #[stream(skip)]
+    local: usize,
+}
+
+fn event() -> Event {
+    Event {
+        kind: "signup".to_string(),
+        count: 2,
+        active: true,
+        note: None,
+        retries: 0,
+        local: 42,
+    }
+}
+
+#[test]
+fn test_derive_encode() {
+    let fields = event().to_stream_fields().unwrap();
+    assert_eq!(
+        fields,
+        vec![
+            ("type".to_string(), b"signup".to_vec()),
+            ("count".to_string(), b"2".to_vec()),
+            ("active".to_string(), b"1".to_vec()),
+            ("retries".to_string(), b"0".to_vec()),
+        ]
+    );
+
+    let mut e = event();
+    e.note = Some("hello".to_string());
+    let fields = e.to_stream_fields().unwrap();
+    assert_eq!(fields[3], ("note".to_string(), b"hello".to_vec()));
+}
+
+#[test]
+fn test_derive_decode() {
+    let id = stream_id(&[
+        ("type", "login"),
+        ("count", "3"),
+        ("active", "0"),
+        ("note", "hi"),
+        ("local", "7"),
+    ]);
+    let e: Event = id.to_message().unwrap();
+    assert_eq!(
+        e,
+        Event {
+            kind: "login".to_string(),
+            count: 3,
+            active: false,
+            note: Some("hi".to_string()),
+            retries: 0,
+            local: 0,
+        }
+    );
+
+    // missing field
+    let id = stream_id(&[("type", "login"), ("active", "0")]);
+    let err = Event::from_stream_id(&id).unwrap_err().to_string();
+    assert!(err.contains("Missing stream message field"));
+    assert!(err.contains("`count`"));
+
+    // mistyped field
+    let id = stream_id(&[("type", "login"), ("count", "x"), ("active", "0")]);
+    let err = Event::from_stream_id(&id).unwrap_err().to_string();
+    assert!(err.contains("Invalid stream message field"));
+    assert!(err.contains("`count`"));
+}
+
+#[test]
+fn test_derive_bool() {
+    // bools are written as 1/0 so they read back from bulk strings
+
+    #[derive(StreamMessage, Debug, PartialEq)]
+    struct Flags {
+        active: bool,
+        visible: Option<bool>,
+        #[stream(default)]
+        hidden: bool,
+    }
+
+    let flags = Flags {
+        active: true,
+        visible: Some(false),
+        hidden: false,
+    };
+    let fields = flags.to_stream_fields().unwrap();
+    assert_eq!(
+        fields,
+        vec![
+            ("active".to_string(), b"1".to_vec()),
+            ("visible".to_string(), b"0".to_vec()),
+            ("hidden".to_string(), b"0".to_vec()),
+        ]
+    );
+
+    let pairs: Vec<(&str, &str)> = fields
+        .iter()
+        .map(|(f, v)| (f.as_str(), std::str::from_utf8(v).unwrap()))
+        .collect();
+    assert_eq!(Flags::from_stream_id(&stream_id(&pairs)).unwrap(), flags);
+
+    let id = stream_id(&[("active", "0")]);
+    let flags = Flags::from_stream_id(&id).unwrap();
+    assert_eq!(flags.visible, None);
+    assert!(!flags.hidden);
+}
+
+#[test]
+fn test_derive_decode_missing() {
+    // types which redis decodes from Nil are still required
+
+    #[derive(StreamMessage, Debug)]
+    struct Flags {
+        name: String,
+        active: bool,
+        tags: Vec<u8>,
+        owner: std::option::Option<String>,
+    }
+
+    let id = stream_id(&[("name", "a"), ("tags", "xy")]);
+    let err = Flags::from_stream_id(&id).unwrap_err().to_string();
+    assert!(err.contains("Missing stream message field"));
+    assert!(err.contains("`active`"));
+
+    let id = stream_id(&[("name", "a"), ("active", "1")]);
+    let err = Flags::from_stream_id(&id).unwrap_err().to_string();
+    assert!(err.contains("Missing stream message field"));
+    assert!(err.contains("`tags`"));
+
+    let id = stream_id(&[("name", "a"), ("active", "1"), ("tags", "xy")]);
+    let flags = Flags::from_stream_id(&id).unwrap();
+    assert!(flags.active);
+    assert_eq!(flags.tags, b"xy".to_vec());
+    assert_eq!(flags.owner, None);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_derive_json() {
+    #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Default)]
+    struct Meta {
+        tags: Vec<String>,
+        source: String,
+    }
+
+    #[derive(StreamMessage, Debug, PartialEq)]
+    struct Tagged {
+        name: String,
+        #[stream(json)]
+        meta: Meta,
+        #[stream(json)]
+        extra: Option<Meta>,
+        #[stream(json, default)]
+        other: Meta,
+    }
+
+    let tagged = Tagged {
+        name: "a".to_string(),
+        meta: Meta {
+            tags: vec!["x".to_string()],
+            source: "web".to_string(),
+        },
+        extra: None,
+        other: Meta::default(),
+    };
+    let fields = tagged.to_stream_fields().unwrap();
+    assert_eq!(
+        fields[1],
+        (
+            "meta".to_string(),
+            br#"{"tags":["x"],"source":"web"}"#.to_vec()
+        )
+    );
+
+    let id = stream_id(&[("name", "a"), ("meta", r#"{"tags":["x"],"source":"web"}"#)]);
+    assert_eq!(Tagged::from_stream_id(&id).unwrap(), tagged);
+
+    let id = stream_id(&[("name", "a"), ("meta", "{")]);
+    let err = Tagged::from_stream_id(&id).unwrap_err().to_string();
+    assert!(err.contains("Invalid json"));
+    assert!(err.contains("`meta`"));
+
+    // a missing json field is an error unless it's an Option
+    let id = stream_id(&[("name", "a")]);
+    let err = Tagged::from_stream_id(&id).unwrap_err().to_string();
+    assert!(err.contains("Missing stream message field"));
+    assert!(err.contains("`meta`"));
+
+    let mut with_extra = tagged;
+    with_extra.extra = Some(Meta::default());
+    let fields = with_extra.to_stream_fields().unwrap();
+    let id = StreamId {
+        id: "1000-0".parse().unwrap(),
+        fields: fields
+            .into_iter()
+            .map(|(field, value)| (field, Value::Data(value)))
+            .collect(),
+    };
+    assert_eq!(Tagged::from_stream_id(&id).unwrap(), with_extra);
+}
+
+#[test]
+fn test_xadd_message() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let mut e = event();
+    e.note = Some("hello".to_string());
+
+    let result: RedisResult<String> = con.xadd_message("k1", "1000-0", &e);
+    assert_eq!(result, Ok("1000-0".to_string()));
+
+    // field/values work with xadd_map too
+    let result: RedisResult<String> = con.xadd_map("k1", "1000-1", e.to_stream_fields().unwrap());
+    assert_eq!(result, Ok("1000-1".to_string()));
+
+    let reply: StreamRangeReply = con.xrange_all("k1").unwrap();
+    assert_eq!(reply.ids.len(), 2);
+    for id in reply.ids.iter() {
+        let decoded: Event = id.to_message().unwrap();
+        assert_eq!(decoded.note, e.note);
+        assert_eq!(decoded.local, 0);
+        assert_eq!(decoded.kind, e.kind);
+    }
+}
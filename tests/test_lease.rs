@@ -0,0 +1,180 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{
+    StreamClaimOptions, StreamClaimReply, StreamCommands, StreamEntryId, StreamLease,
+    StreamPendingCountReply, StreamReadOptions, StreamReadReply,
+};
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+fn test_lease() {
+    // Tests the following....
+    // leased ids stay fresh and aren't claimed by idle time
+    // heartbeats don't bump the delivery count
+    // ids claimed by another consumer are lost and not taken back
+    // ack stops heartbeating and acks the ids still held
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "0");
+    for i in 0..3 {
+        let _: RedisResult<String> = con.xadd("k1", format!("1000-{}", i), &[("idx", i)]);
+    }
+    let reply: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+    let ids: Vec<StreamEntryId> = reply.keys[0].ids.iter().map(|msg| msg.id).collect();
+
+    // only lease the first two, the third is pending in the same range
+    let lease = StreamLease::start(
+        ctx.connection(),
+        "k1",
+        "g1",
+        "c1",
+        &ids[..2],
+        Duration::from_millis(10),
+    );
+
+    sleep(Duration::from_millis(100));
+
+    // only the unleased id is idle long enough
+    let reply: StreamClaimReply = con.xclaim("k1", "g1", "c2", 80, &ids).unwrap();
+    assert_eq!(reply.ids.len(), 1);
+    assert_eq!(reply.ids[0].id, "1000-2");
+
+    let reply: StreamPendingCountReply = con
+        .xpending_consumer_count("k1", "g1", "-", "+", 10, "c1")
+        .unwrap();
+    assert_eq!(reply.ids.len(), 2);
+    assert!(reply.ids.iter().all(|pending| pending.times_delivered == 1));
+
+    // steal one of the leased ids
+    let _: Vec<StreamEntryId> = con
+        .xclaim_options(
+            "k1",
+            "g1",
+            "c2",
+            0,
+            &[ids[1]],
+            StreamClaimOptions::default().with_justid(),
+        )
+        .unwrap();
+    sleep(Duration::from_millis(50));
+    assert_eq!(lease.lost(), vec![ids[1]]);
+
+    let reply: StreamPendingCountReply = con
+        .xpending_consumer_count("k1", "g1", "-", "+", 10, "c2")
+        .unwrap();
+    assert_eq!(reply.ids.len(), 2);
+
+    // ack only acks the ids still held and hands back the connection
+    let report = lease.ack().unwrap();
+    assert_eq!(report.acked, vec![ids[0]]);
+    assert_eq!(report.lost, vec![ids[1]]);
+    assert!(report.heartbeat_error.is_none());
+
+    let mut lease_con = report.con;
+    let reply: StreamPendingCountReply = lease_con
+        .xpending_consumer_count("k1", "g1", "-", "+", 10, "c1")
+        .unwrap();
+    assert_eq!(reply.ids.len(), 0);
+    let reply: StreamPendingCountReply = lease_con
+        .xpending_consumer_count("k1", "g1", "-", "+", 10, "c2")
+        .unwrap();
+    assert_eq!(reply.ids.len(), 2);
+}
+
+#[test]
+fn test_lease_drop() {
+    // Tests the following....
+    // dropping a lease stops heartbeating and leaves the ids pending
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "0");
+    let _: RedisResult<String> = con.xadd("k1", "1000-0", &[("idx", 0)]);
+    let _: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+
+    let ids: Vec<StreamEntryId> = vec!["1000-0".parse().unwrap()];
+    let lease = StreamLease::start(
+        ctx.connection(),
+        "k1",
+        "g1",
+        "c1",
+        &ids,
+        Duration::from_millis(10),
+    );
+    sleep(Duration::from_millis(30));
+    drop(lease);
+    sleep(Duration::from_millis(60));
+
+    let reply: StreamClaimReply = con.xclaim("k1", "g1", "c2", 50, &ids).unwrap();
+    assert_eq!(reply.ids.len(), 1);
+}
+
+#[test]
+fn test_lease_heartbeat_error() {
+    // Tests the following....
+    // a failed heartbeat is reported and doesn't stop ack
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let read = |con: &mut redis::Connection| {
+        let _: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "0");
+        let _: RedisResult<String> = con.xadd("k1", "1000-0", &[("idx", 0)]);
+        let _: StreamReadReply = con
+            .xread_options(
+                &["k1"],
+                &[">"],
+                StreamReadOptions::default().group("g1", "c1"),
+            )
+            .unwrap();
+    };
+    read(&mut con);
+
+    let ids: Vec<StreamEntryId> = vec!["1000-0".parse().unwrap()];
+    let lease = StreamLease::start(
+        ctx.connection(),
+        "k1",
+        "g1",
+        "c1",
+        &ids,
+        Duration::from_millis(10),
+    );
+
+    // the heartbeat fails with NOGROUP while the stream is gone
+    let _: usize = redis::cmd("DEL").arg("k1").query(&mut con).unwrap();
+    sleep(Duration::from_millis(50));
+    read(&mut con);
+
+    let report = lease.ack().unwrap();
+    assert!(report.heartbeat_error.is_some());
+    assert_eq!(report.acked, ids);
+
+    let reply: StreamPendingCountReply = con
+        .xpending_consumer_count("k1", "g1", "-", "+", 10, "c1")
+        .unwrap();
+    assert_eq!(reply.ids.len(), 0);
+}
@@ -0,0 +1,454 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::{from_redis_value, ErrorKind, FromRedisValue, RedisResult, Value};
+
+use redis_streams::{
+    StreamAutoClaimReply, StreamBytesRangeReply, StreamBytesReadReply, StreamClaimReply,
+    StreamEntryId, StreamId, StreamInfoConsumersReply, StreamInfoGroupsReply,
+    StreamInfoStreamFullReply, StreamInfoStreamReply, StreamPendingCountReply, StreamPendingReply,
+    StreamRangeReply, StreamReadReply,
+};
+
+fn data(s: &str) -> Value {
+    Value::Data(s.as_bytes().to_vec())
+}
+
+fn bulk(values: Vec<Value>) -> Value {
+    Value::Bulk(values)
+}
+
+fn entry(id: &str, fields: &[(&str, &str)]) -> Value {
+    let mut values = vec![];
+    for (f, v) in fields {
+        values.push(data(f));
+        values.push(data(v));
+    }
+    bulk(vec![data(id), bulk(values)])
+}
+
+/// Asserts the parse failed with a `TypeError` whose message contains `msg`.
+fn assert_type_error<T: FromRedisValue + std::fmt::Debug>(v: Value, msg: &str) {
+    let result: RedisResult<T> = from_redis_value(&v);
+    let err = result.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeError);
+    assert!(
+        err.to_string().contains(msg),
+        "{:?} doesn't contain {:?}",
+        err.to_string(),
+        msg
+    );
+}
+
+#[test]
+fn test_parse_stream_id() {
+    let id = StreamId::from_bulk_value(&entry("1000-0", &[("a", "1")])).unwrap();
+    assert_eq!(id.id, StreamEntryId::new(1000, 0));
+    assert_eq!(id.get("a"), Some(1));
+
+    // JUSTID
+    let id = StreamId::from_bulk_value(&data("1000-1")).unwrap();
+    assert_eq!(id.id, "1000-1");
+    assert_eq!(id.is_empty(), true);
+
+    let err = StreamId::from_bulk_value(&Value::Int(1)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeError);
+    assert!(err.to_string().contains("but got integer 1"));
+
+    // missing fields
+    let err = StreamId::from_bulk_value(&bulk(vec![data("1000-0")])).unwrap_err();
+    assert!(err.to_string().contains("[id, [field, value, ...]]"));
+
+    // odd field/values
+    let err =
+        StreamId::from_bulk_value(&bulk(vec![data("1000-0"), bulk(vec![data("a")])])).unwrap_err();
+    assert!(err.to_string().contains("even number"));
+
+    // bad id
+    let err = StreamId::from_bulk_value(&entry("abc", &[])).unwrap_err();
+    assert!(err.to_string().contains("Invalid stream id"));
+}
+
+#[test]
+fn test_parse_read_reply() {
+    let v = bulk(vec![
+        bulk(vec![
+            data("k1"),
+            bulk(vec![entry("1000-0", &[("a", "1")]), entry("1000-1", &[])]),
+        ]),
+        bulk(vec![data("k2"), bulk(vec![entry("2000-0", &[("b", "2")])])]),
+    ]);
+    let reply: StreamReadReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.keys.len(), 2);
+    assert_eq!(reply.keys[0].key, "k1");
+    assert_eq!(reply.keys[0].ids.len(), 2);
+    assert_eq!(reply.keys[1].ids[0].get("b"), Some("2".to_string()));
+
+    let reply: StreamBytesReadReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.keys[1].key, b"k2".to_vec());
+
+    // blocking timeout
+    let reply: StreamReadReply = from_redis_value(&Value::Nil).unwrap();
+    assert_eq!(reply.keys.len(), 0);
+
+    assert_type_error::<StreamReadReply>(Value::Okay, "but got OK");
+    assert_type_error::<StreamReadReply>(bulk(vec![data("k1")]), "[key, [entry, ...]]");
+    assert_type_error::<StreamReadReply>(
+        bulk(vec![bulk(vec![data("k1"), data("x")])]),
+        "an array of entries",
+    );
+    assert_type_error::<StreamReadReply>(
+        bulk(vec![bulk(vec![Value::Int(1), bulk(vec![])])]),
+        "a stream key",
+    );
+
+    // non-utf8 keys only parse as bytes
+    let v = bulk(vec![bulk(vec![
+        Value::Data(vec![0xff]),
+        bulk(vec![entry("1000-0", &[])]),
+    ])]);
+    assert_type_error::<StreamReadReply>(v.clone(), "binary data of 1 bytes");
+    let reply: StreamBytesReadReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.keys[0].key, vec![0xff]);
+}
+
+#[test]
+fn test_parse_range_and_claim_reply() {
+    let v = bulk(vec![entry("1000-0", &[("a", "1")]), entry("1000-1", &[])]);
+    let reply: StreamRangeReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.ids.len(), 2);
+    let reply: StreamBytesRangeReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.ids[0].get(b"a"), Some(1));
+
+    assert_type_error::<StreamRangeReply>(data("x"), "an array of entries");
+    assert_type_error::<StreamRangeReply>(bulk(vec![Value::Int(1)]), "Invalid stream entry");
+    assert_type_error::<StreamBytesRangeReply>(bulk(vec![data("1000-0")]), "[id, [field");
+
+    // deleted entries are nil and JUSTID replies are bare ids
+    let v = bulk(vec![entry("1000-0", &[]), Value::Nil, data("1000-2")]);
+    let reply: StreamClaimReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.ids.len(), 2);
+    assert_eq!(reply.ids[1].id, "1000-2");
+}
+
+#[test]
+fn test_parse_autoclaim_reply() {
+    let v = bulk(vec![
+        data("1000-5"),
+        bulk(vec![
+            entry("1000-0", &[("a", "1")]),
+            bulk(vec![data("1000-1"), Value::Nil]),
+        ]),
+        bulk(vec![data("1000-2")]),
+    ]);
+    let reply: StreamAutoClaimReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.next_stream_id, "1000-5");
+    assert_eq!(reply.claimed.len(), 1);
+    assert_eq!(reply.deleted_ids, vec![StreamEntryId::new(1000, 2)]);
+
+    // Redis 6.2 has no deleted ids
+    let v = bulk(vec![data("0-0"), bulk(vec![data("1000-0")])]);
+    let reply: StreamAutoClaimReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.claimed[0].id, "1000-0");
+    assert_eq!(reply.deleted_ids.len(), 0);
+
+    assert_type_error::<StreamAutoClaimReply>(bulk(vec![data("0-0")]), "[next-id");
+    assert_type_error::<StreamAutoClaimReply>(
+        bulk(vec![data("0-0"), data("x")]),
+        "an array of entries",
+    );
+    assert_type_error::<StreamAutoClaimReply>(
+        bulk(vec![data("0-0"), bulk(vec![]), data("x")]),
+        "an array of deleted ids",
+    );
+}
+
+#[test]
+fn test_parse_pending_reply() {
+    let v = bulk(vec![Value::Int(0), Value::Nil, Value::Nil, Value::Nil]);
+    let reply: StreamPendingReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.count(), 0);
+
+    let v = bulk(vec![
+        Value::Int(3),
+        data("1000-0"),
+        data("1000-2"),
+        bulk(vec![
+            bulk(vec![data("c1"), data("2")]),
+            bulk(vec![data("c2"), data("1")]),
+        ]),
+    ]);
+    let reply: StreamPendingReply = from_redis_value(&v).unwrap();
+    match reply {
+        StreamPendingReply::Data(data) => {
+            assert_eq!(data.count, 3);
+            assert_eq!(data.end_id, "1000-2");
+            assert_eq!(data.consumers[1].name, "c2");
+            assert_eq!(data.consumers[0].pending, 2);
+        }
+        StreamPendingReply::Empty => panic!("expected pending data"),
+    }
+
+    assert_type_error::<StreamPendingReply>(bulk(vec![Value::Int(0)]), "[count, start-id");
+    assert_type_error::<StreamPendingReply>(
+        bulk(vec![Value::Int(1), Value::Nil, Value::Nil, Value::Nil]),
+        "expected a start id but got nil",
+    );
+    assert_type_error::<StreamPendingReply>(
+        bulk(vec![
+            Value::Int(1),
+            data("1000-0"),
+            data("1000-0"),
+            bulk(vec![bulk(vec![data("c1")])]),
+        ]),
+        "[consumer, count]",
+    );
+    assert_type_error::<StreamPendingReply>(
+        bulk(vec![
+            Value::Int(1),
+            data("1000-0"),
+            data("1000-0"),
+            bulk(vec![bulk(vec![data("c1"), data("x")])]),
+        ]),
+        "a consumer pending count",
+    );
+}
+
+#[test]
+fn test_parse_pending_count_reply() {
+    let v = bulk(vec![bulk(vec![
+        data("1000-0"),
+        data("c1"),
+        Value::Int(15),
+        Value::Int(2),
+    ])]);
+    let reply: StreamPendingCountReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.ids[0].id, "1000-0");
+    assert_eq!(reply.ids[0].consumer, "c1");
+    assert_eq!(reply.ids[0].last_delivered_ms, 15);
+    assert_eq!(reply.ids[0].times_delivered, 2);
+
+    let reply: StreamPendingCountReply = from_redis_value(&bulk(vec![])).unwrap();
+    assert_eq!(reply.ids.len(), 0);
+
+    assert_type_error::<StreamPendingCountReply>(
+        bulk(vec![bulk(vec![data("1000-0"), data("c1"), Value::Int(15)])]),
+        "[id, consumer, last-delivered-ms, times-delivered]",
+    );
+    assert_type_error::<StreamPendingCountReply>(
+        bulk(vec![bulk(vec![
+            data("1000-0"),
+            data("c1"),
+            data("x"),
+            Value::Int(2),
+        ])]),
+        "an idle time",
+    );
+}
+
+#[test]
+fn test_parse_info_replies() {
+    let v = bulk(vec![
+        data("length"),
+        Value::Int(2),
+        data("radix-tree-keys"),
+        Value::Int(1),
+        data("radix-tree-nodes"),
+        Value::Int(1),
+        data("groups"),
+        Value::Int(1),
+        data("last-generated-id"),
+        data("1000-1"),
+        data("first-entry"),
+        entry("1000-0", &[("a", "1")]),
+        data("last-entry"),
+        Value::Nil,
+    ]);
+    let reply: StreamInfoStreamReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.length, 2);
+    assert_eq!(reply.last_generated_id, "1000-1");
+    assert_eq!(reply.first_entry.id, "1000-0");
+    assert_eq!(reply.last_entry.id, StreamEntryId::MIN);
+    assert_eq!(reply.max_deleted_entry_id, None);
+    assert_eq!(reply.extra.len(), 0);
+
+    assert_type_error::<StreamInfoStreamReply>(bulk(vec![data("length")]), "even number");
+    assert_type_error::<StreamInfoStreamReply>(
+        bulk(vec![data("length"), data("x")]),
+        "a valid `length`",
+    );
+    assert_type_error::<StreamInfoStreamReply>(
+        bulk(vec![data("length"), Value::Int(2)]),
+        "missing `",
+    );
+
+    let v = bulk(vec![bulk(vec![
+        data("name"),
+        data("g1"),
+        data("consumers"),
+        Value::Int(2),
+        data("pending"),
+        Value::Int(3),
+        data("last-delivered-id"),
+        data("1000-0"),
+        data("entries-read"),
+        Value::Nil,
+        data("lag"),
+        Value::Int(1),
+        data("future-field"),
+        data("x"),
+    ])]);
+    let reply: StreamInfoGroupsReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.groups[0].name, "g1");
+    assert_eq!(reply.groups[0].consumers, 2);
+    assert_eq!(reply.groups[0].last_delivered_id, "1000-0");
+    assert_eq!(reply.groups[0].entries_read, None);
+    assert_eq!(reply.groups[0].lag, Some(1));
+    assert_eq!(reply.groups[0].extra.get("future-field"), Some(&data("x")));
+
+    assert_type_error::<StreamInfoGroupsReply>(Value::Int(1), "an array of groups");
+    assert_type_error::<StreamInfoGroupsReply>(
+        bulk(vec![bulk(vec![
+            data("name"),
+            data("g1"),
+            data("consumers"),
+            Value::Int(2),
+            data("pending"),
+            data("x"),
+        ])]),
+        "a valid `pending`",
+    );
+    assert_type_error::<StreamInfoGroupsReply>(
+        bulk(vec![bulk(vec![data("consumers"), Value::Int(2)])]),
+        "missing `name`",
+    );
+
+    let v = bulk(vec![bulk(vec![
+        data("name"),
+        data("c1"),
+        data("pending"),
+        Value::Int(1),
+        data("idle"),
+        Value::Int(100),
+    ])]);
+    let reply: StreamInfoConsumersReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.consumers[0].name, "c1");
+    assert_eq!(reply.consumers[0].idle, 100);
+
+    assert_type_error::<StreamInfoConsumersReply>(bulk(vec![data("name")]), "[name, value, ...]");
+}
+
+#[test]
+fn test_parse_info_stream_full_reply() {
+    let v = bulk(vec![
+        data("length"),
+        Value::Int(2),
+        data("radix-tree-keys"),
+        Value::Int(1),
+        data("radix-tree-nodes"),
+        Value::Int(2),
+        data("last-generated-id"),
+        data("1000-1"),
+        data("max-deleted-entry-id"),
+        data("0-0"),
+        data("entries-added"),
+        Value::Int(2),
+        data("recorded-first-entry-id"),
+        data("1000-0"),
+        data("entries"),
+        bulk(vec![entry("1000-0", &[("a", "1")]), entry("1000-1", &[])]),
+        data("groups"),
+        bulk(vec![bulk(vec![
+            data("name"),
+            data("g1"),
+            data("last-delivered-id"),
+            data("1000-1"),
+            data("entries-read"),
+            Value::Int(2),
+            data("lag"),
+            Value::Int(0),
+            data("pel-count"),
+            Value::Int(1),
+            data("pending"),
+            bulk(vec![bulk(vec![
+                data("1000-1"),
+                data("c1"),
+                Value::Int(15),
+                Value::Int(1),
+            ])]),
+            data("consumers"),
+            bulk(vec![bulk(vec![
+                data("name"),
+                data("c1"),
+                data("seen-time"),
+                Value::Int(15),
+                data("active-time"),
+                Value::Int(15),
+                data("pel-count"),
+                Value::Int(1),
+                data("pending"),
+                bulk(vec![bulk(vec![
+                    data("1000-1"),
+                    Value::Int(15),
+                    Value::Int(1),
+                ])]),
+            ])]),
+        ])]),
+        data("future-field"),
+        Value::Int(7),
+    ]);
+    let reply: StreamInfoStreamFullReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.length, 2);
+    assert_eq!(reply.radix_tree_keys, 1);
+    assert_eq!(reply.radix_tree_nodes, 2);
+    assert_eq!(reply.max_deleted_entry_id, Some(StreamEntryId::MIN));
+    assert_eq!(reply.entries_added, Some(2));
+    assert_eq!(reply.entries.len(), 2);
+    assert_eq!(reply.extra.get("future-field"), Some(&Value::Int(7)));
+
+    let group = &reply.groups[0];
+    assert_eq!(group.name, "g1");
+    assert_eq!(group.lag, Some(0));
+    assert_eq!(group.pel_count, 1);
+    assert_eq!(group.pending[0].consumer, "c1");
+    assert_eq!(group.pending[0].delivery_count, 1);
+    assert_eq!(group.extra.len(), 0);
+
+    let consumer = &group.consumers[0];
+    assert_eq!(consumer.name, "c1");
+    assert_eq!(consumer.active_time, Some(15));
+    assert_eq!(consumer.pending[0].id, "1000-1");
+    assert_eq!(consumer.pending[0].consumer, "");
+
+    // Redis 6 has no newer fields
+    let v = bulk(vec![
+        data("length"),
+        Value::Int(0),
+        data("radix-tree-keys"),
+        Value::Int(0),
+        data("radix-tree-nodes"),
+        Value::Int(1),
+        data("last-generated-id"),
+        data("0-0"),
+        data("groups"),
+        bulk(vec![]),
+    ]);
+    let reply: StreamInfoStreamFullReply = from_redis_value(&v).unwrap();
+    assert_eq!(reply.entries_added, None);
+    assert_eq!(reply.groups.len(), 0);
+
+    assert_type_error::<StreamInfoStreamFullReply>(
+        bulk(vec![data("groups"), data("x")]),
+        "an array of groups",
+    );
+    assert_type_error::<StreamInfoStreamFullReply>(
+        bulk(vec![
+            data("groups"),
+            bulk(vec![bulk(vec![
+                data("pending"),
+                bulk(vec![bulk(vec![data("1000-1"), Value::Int(15)])]),
+            ])]),
+        ]),
+        "[id, consumer, delivery-time, delivery-count]",
+    );
+}
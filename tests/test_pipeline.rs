@@ -0,0 +1,117 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::{RedisResult, Value};
+
+use redis_streams::{
+    StreamClaimReply, StreamCommands, StreamInfoGroupsReply, StreamMaxlen, StreamPendingCountReply,
+    StreamPendingOptions, StreamPipelineCommands, StreamRangeReply, StreamReadOptions,
+    StreamReadReply,
+};
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+fn test_pipeline_packed_cmds() {
+    let mut pipe = redis::pipe();
+    pipe.xadd("k1", "*", &[("a", "b")])
+        .xack("k1", "g1", &["1000-0"])
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().count(2).group("g1", "c1"),
+        )
+        .xtrim("k1", StreamMaxlen::Equals(5));
+
+    let mut expected = redis::pipe();
+    expected
+        .cmd("XADD")
+        .arg("k1")
+        .arg("*")
+        .arg("a")
+        .arg("b")
+        .cmd("XACK")
+        .arg("k1")
+        .arg("g1")
+        .arg("1000-0")
+        .cmd("XREADGROUP")
+        .arg("COUNT")
+        .arg("2")
+        .arg("GROUP")
+        .arg("g1")
+        .arg("c1")
+        .arg("STREAMS")
+        .arg("k1")
+        .arg(">")
+        .cmd("XTRIM")
+        .arg("k1")
+        .arg("MAXLEN")
+        .arg("=")
+        .arg("5");
+
+    assert_eq!(pipe.get_packed_pipeline(), expected.get_packed_pipeline());
+}
+
+#[test]
+fn test_pipeline() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    // batch adds and group creation in one round trip
+    let mut pipe = redis::pipe();
+    for i in 0..5 {
+        pipe.xadd("k1", format!("1000-{}", i), &[("i", i)]);
+    }
+    pipe.xgroup_create("k1", "g1", "0").ignore();
+    let ids: Vec<String> = pipe.query(&mut con).unwrap();
+    assert_eq!(ids.len(), 5);
+    assert_eq!(&ids[4], "1000-4");
+
+    // decode typed replies from a mixed pipeline
+    let (reply, groups, len): (StreamReadReply, StreamInfoGroupsReply, usize) = redis::pipe()
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().count(3).group("g1", "c1"),
+        )
+        .xinfo_groups("k1")
+        .xlen("k1")
+        .query(&mut con)
+        .unwrap();
+    assert_eq!(reply.keys[0].ids.len(), 3);
+    assert_eq!(&groups.groups[0].name, "g1");
+    assert_eq!(groups.groups[0].pending, 3);
+    assert_eq!(len, 5);
+
+    // ack and add atomically
+    let (acked, id): (usize, String) = redis::pipe()
+        .atomic()
+        .xack("k1", "g1", &["1000-0", "1000-1"])
+        .xadd("k1", "1000-5", &[("i", 5)])
+        .query(&mut con)
+        .unwrap();
+    assert_eq!(acked, 2);
+    assert_eq!(&id, "1000-5");
+
+    let (pending, claimed, range): (StreamPendingCountReply, StreamClaimReply, StreamRangeReply) =
+        redis::pipe()
+            .xpending_options("k1", "g1", StreamPendingOptions::default())
+            .xclaim("k1", "g1", "c2", 0, &["1000-2"])
+            .xrange_count("k1", "-", "+", 2)
+            .query(&mut con)
+            .unwrap();
+    assert_eq!(pending.ids.len(), 1);
+    assert_eq!(claimed.ids[0].id, "1000-2");
+    assert_eq!(range.ids.len(), 2);
+
+    // errors surface when the pipeline is queried
+    let result: RedisResult<Vec<Value>> =
+        redis::pipe().xgroup_create("k1", "g1", "0").query(&mut con);
+    assert!(result.is_err());
+
+    // connection commands still work alongside
+    let len: usize = con.xlen("k1").unwrap();
+    assert_eq!(len, 6);
+}
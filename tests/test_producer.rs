@@ -0,0 +1,177 @@
+extern crate redis;
+extern crate redis_streams;
+
+use futures::SinkExt;
+
+use redis::RedisError;
+
+use redis_streams::{
+    StreamCommands, StreamEntryId, StreamMaxlen, StreamProducer, StreamProducerMessage,
+    StreamProducerOptions, StreamRangeReply,
+};
+
+use std::thread;
+use std::time::Duration;
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+fn test_producer() {
+    // Tests the following....
+    // messages are written in order and handles resolve to their ids
+    // flush writes a partial batch before the linger time
+    // close hands back the connection
+
+    let ctx = TestContext::new();
+
+    let options = StreamProducerOptions::default()
+        .batch_size(100)
+        .linger(Duration::from_secs(60));
+    let producer = StreamProducer::start(ctx.connection(), options);
+
+    let handles: Vec<_> = (0..250)
+        .map(|i| producer.xadd("k1", &[("idx", i)]).unwrap())
+        .collect();
+    producer.flush().unwrap();
+    let ids: Vec<StreamEntryId> = handles.into_iter().map(|h| h.wait().unwrap()).collect();
+    assert_eq!(ids.len(), 250);
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let mut con = producer.close().unwrap();
+    let reply: StreamRangeReply = con.xrange_all("k1").unwrap();
+    assert_eq!(reply.ids.len(), 250);
+    assert_eq!(reply.ids[0].id, ids[0]);
+    assert_eq!(reply.ids[249].get("idx"), Some(249));
+}
+
+#[test]
+fn test_producer_options() {
+    // Tests the following....
+    // linger writes a partial batch on its own
+    // maxlen trims each stream
+    // a tiny in-flight limit still makes progress
+    // a failed XADD is reported to its handle
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let options = StreamProducerOptions::default()
+        .linger(Duration::from_millis(1))
+        .max_in_flight_bytes(1)
+        .maxlen(StreamMaxlen::Equals(5));
+    let producer = StreamProducer::start(ctx.connection(), options);
+
+    let handles: Vec<_> = (0..20)
+        .map(|i| producer.xadd("k1", &[("idx", i)]).unwrap())
+        .collect();
+    for handle in handles {
+        assert!(handle.wait().is_ok());
+    }
+    let len: usize = con.xlen("k1").unwrap();
+    assert_eq!(len, 5);
+
+    let _: () = redis::cmd("SET")
+        .arg("k2")
+        .arg("v")
+        .query(&mut con)
+        .unwrap();
+    let handle = producer.xadd("k2", &[("idx", 0)]).unwrap();
+    assert!(handle.wait().is_err());
+}
+
+#[test]
+fn test_producer_sink() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let mut sink =
+        StreamProducer::start(ctx.connection(), StreamProducerOptions::default()).into_sink();
+    block_on_all(async move {
+        let (message, handle) = StreamProducerMessage::new("k1", &[("name", "signup")]);
+        sink.send(message).await?;
+        let id = handle.await?;
+
+        let reply: StreamRangeReply = con.xrange_all("k1").unwrap();
+        assert_eq!(reply.ids[0].id, id);
+        assert_eq!(reply.ids[0].get("name"), Some("signup".to_string()));
+
+        Ok::<_, RedisError>(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_producer_per_key() {
+    // Tests the following....
+    // each key is batched on its own
+    // failed messages don't fail the rest of their batch
+    // senders can be cloned across threads
+    // senders fail once the producer is closed
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let options = StreamProducerOptions::default()
+        .batch_size(2)
+        .linger(Duration::from_secs(60));
+    let producer = StreamProducer::start(ctx.connection(), options);
+
+    // k2 fills its batch and is written while k1 waits for its linger time
+    let k1 = producer.xadd("k1", &[("idx", 0)]).unwrap();
+    let k2: Vec<_> = (0..2)
+        .map(|i| producer.xadd("k2", &[("idx", i)]).unwrap())
+        .collect();
+    for handle in k2 {
+        assert!(handle.wait().is_ok());
+    }
+    let len: usize = con.xlen("k1").unwrap();
+    assert_eq!(len, 0);
+    producer.flush().unwrap();
+    assert!(k1.wait().is_ok());
+
+    // a failing XADD only fails its own handle
+    let _: () = redis::cmd("SET")
+        .arg("k3")
+        .arg("v")
+        .query(&mut con)
+        .unwrap();
+    let empty: &[(&str, &str)] = &[];
+    let good = producer.xadd("k1", &[("idx", 1)]).unwrap();
+    let bad = producer.xadd("k1", empty).unwrap();
+    let wrong = producer.xadd("k3", &[("idx", 0)]).unwrap();
+    producer.flush().unwrap();
+    assert!(good.wait().is_ok());
+    assert!(bad.wait().is_err());
+    assert!(wrong.wait().is_err());
+    let len: usize = con.xlen("k1").unwrap();
+    assert_eq!(len, 2);
+
+    let workers: Vec<_> = (0..4)
+        .map(|worker| {
+            let sender = producer.sender();
+            thread::spawn(move || {
+                let key = format!("w{}", worker);
+                let handles: Vec<_> = (0..10)
+                    .map(|i| sender.xadd(&key, &[("idx", i)]).unwrap())
+                    .collect();
+                sender.flush().unwrap();
+                for handle in handles {
+                    handle.wait().unwrap();
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    for worker in 0..4 {
+        let len: usize = con.xlen(format!("w{}", worker)).unwrap();
+        assert_eq!(len, 10);
+    }
+
+    let sender = producer.sender();
+    producer.close().unwrap();
+    assert!(sender.xadd("k1", &[("idx", 1)]).is_err());
+}
@@ -0,0 +1,78 @@
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{
+    StreamCommands, StreamInfoConsumersReply, StreamPendingCountReply, StreamReadOptions,
+    StreamReadReply, StreamReaper,
+};
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::support::*;
+
+mod support;
+
+#[test]
+fn test_reaper() {
+    // Tests the following....
+    // pending messages of idle consumers are claimed for the reaper
+    // busy consumers are left alone
+    // idle consumers without pending messages are deleted
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "0");
+    for i in 0..5 {
+        let _: RedisResult<String> = con.xadd("k1", format!("1000-{}", i), &[("idx", i)]);
+    }
+
+    // c1 reads 3 messages and dies
+    let _: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1").count(3),
+        )
+        .unwrap();
+
+    sleep(Duration::from_millis(50));
+
+    // c2 reads the rest and stays busy
+    let _: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c2"),
+        )
+        .unwrap();
+
+    // a small page size makes the reaper follow the pending entries list
+    let reaper = StreamReaper::new("k1", "g1", "c3", 40)
+        .count(2)
+        .delete_idle_consumers(40);
+
+    let report = reaper.reap(&mut con).unwrap();
+    assert_eq!(report.reaped_consumers, vec!["c1".to_string()]);
+    assert_eq!(report.claimed.len(), 3);
+    assert_eq!(report.claimed[0].id, "1000-0");
+    assert_eq!(report.claimed[2].get("idx"), Some(2));
+    // c1 had nothing pending left after being reaped
+    assert_eq!(report.deleted_consumers, vec!["c1".to_string()]);
+
+    let reply: StreamPendingCountReply = con
+        .xpending_consumer_count("k1", "g1", "-", "+", 10, "c3")
+        .unwrap();
+    assert_eq!(reply.ids.len(), 3);
+
+    let reply: StreamInfoConsumersReply = con.xinfo_consumers("k1", "g1").unwrap();
+    let names: Vec<&str> = reply.consumers.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["c2", "c3"]);
+
+    // nothing else is idle long enough
+    let report = reaper.reap(&mut con).unwrap();
+    assert!(report.is_empty());
+}
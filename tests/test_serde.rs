@@ -0,0 +1,177 @@
+#![cfg(feature = "serde")]
+
+extern crate redis;
+extern crate redis_streams;
+
+use redis::RedisResult;
+
+use redis_streams::{
+    to_stream_fields, StreamCommands, StreamRangeReply, StreamReadOptions, StreamReadReply,
+    StreamTypedRangeReply, StreamTypedReadReply,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+use crate::support::*;
+
+mod support;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Signup,
+    Login,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Event {
+    name: String,
+    count: u32,
+    score: f64,
+    active: bool,
+    kind: Kind,
+    note: Option<String>,
+}
+
+fn event(name: &str, count: u32) -> Event {
+    Event {
+        name: name.to_string(),
+        count,
+        score: 1.5,
+        active: true,
+        kind: Kind::Signup,
+        note: None,
+    }
+}
+
+#[test]
+fn test_to_stream_fields() {
+    let fields = to_stream_fields(&event("signup", 2)).unwrap();
+    let fields: Vec<(&str, &[u8])> = fields
+        .iter()
+        .map(|(f, v)| (f.as_str(), v.as_slice()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("name", &b"signup"[..]),
+            ("count", &b"2"[..]),
+            ("score", &b"1.5"[..]),
+            ("active", &b"1"[..]),
+            ("kind", &b"signup"[..]),
+        ]
+    );
+
+    // maps flatten the same way
+    let mut map = BTreeMap::new();
+    map.insert("a", 1);
+    map.insert("b", 2);
+    let fields = to_stream_fields(&map).unwrap();
+    assert_eq!(fields[1], ("b".to_string(), b"2".to_vec()));
+
+    // nested values are rejected and name the field
+    #[derive(Serialize)]
+    struct Nested {
+        tags: Vec<String>,
+    }
+    let err = to_stream_fields(&Nested { tags: vec![] }).unwrap_err();
+    assert!(err.to_string().contains("`tags`"));
+
+    // so are non-struct values
+    assert!(to_stream_fields(&"hello").is_err());
+}
+
+#[test]
+fn test_stream_id_deserialize() {
+    let id = stream_id(&[
+        ("name", "login"),
+        ("count", "3"),
+        ("score", "0.25"),
+        ("active", "false"),
+        ("kind", "login"),
+        ("note", "first"),
+        ("extra", "ignored"),
+    ]);
+    let event: Event = id.deserialize().unwrap();
+    assert_eq!(
+        event,
+        Event {
+            name: "login".to_string(),
+            count: 3,
+            score: 0.25,
+            active: false,
+            kind: Kind::Login,
+            note: Some("first".to_string()),
+        }
+    );
+
+    // missing field
+    let id = stream_id(&[("name", "login"), ("count", "3")]);
+    let err = id.deserialize::<Event>().unwrap_err().to_string();
+    assert!(err.contains("1000-0"));
+    assert!(err.contains("missing field `score`"));
+
+    // mistyped field
+    let id = stream_id(&[
+        ("name", "login"),
+        ("count", "three"),
+        ("score", "0.25"),
+        ("active", "1"),
+        ("kind", "login"),
+    ]);
+    let err = id.deserialize::<Event>().unwrap_err().to_string();
+    assert!(err.contains("invalid value for field `count`"));
+}
+
+#[test]
+fn test_xadd_serialize() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let first = event("signup", 1);
+    let mut second = event("login", 2);
+    second.kind = Kind::Login;
+    second.note = Some("hello".to_string());
+
+    let result: RedisResult<String> = con.xadd_serialize("k1", "1000-0", &first);
+    assert_eq!(result, Ok("1000-0".to_string()));
+    let result: RedisResult<String> = con.xadd_serialize("k1", "1000-1", &second);
+    assert_eq!(result, Ok("1000-1".to_string()));
+
+    // typed range reply
+    let reply: StreamTypedRangeReply<Event> = con.xrange_all("k1").unwrap();
+    assert_eq!(reply.ids.len(), 2);
+    assert_eq!(reply.ids[0].id, "1000-0");
+    assert_eq!(reply.ids[0].data, first);
+    assert_eq!(reply.ids[1].data, second);
+
+    // or decode an untyped reply
+    let reply: StreamRangeReply = con.xrange_all("k1").unwrap();
+    let typed = reply.deserialize::<Event>().unwrap();
+    assert_eq!(typed.ids[1].data, second);
+    assert_eq!(reply.ids[1].deserialize::<Event>().unwrap(), second);
+
+    // typed read reply
+    let reply: StreamTypedReadReply<Event> = redis::cmd("XREAD")
+        .arg("STREAMS")
+        .arg("k1")
+        .arg("0")
+        .query(&mut con)
+        .unwrap();
+    assert_eq!(reply.keys[0].key, "k1");
+    assert_eq!(reply.keys[0].ids[0].data, first);
+
+    let reply: StreamReadReply = con
+        .xread_options(&["k1"], &["0"], StreamReadOptions::default().count(1))
+        .unwrap();
+    let typed = reply.deserialize::<Event>().unwrap();
+    assert_eq!(typed.keys[0].ids.len(), 1);
+    assert_eq!(typed.keys[0].ids[0].data, first);
+
+    // messages that don't match the type surface an error
+    let _: RedisResult<String> = con.xadd("k1", "1000-2", &[("name", "bad")]);
+    let result: RedisResult<StreamTypedRangeReply<Event>> = con.xrange_all("k1");
+    assert!(result.unwrap_err().to_string().contains("missing field"));
+}
@@ -1,18 +1,22 @@
 extern crate redis;
 extern crate redis_streams;
 
-use redis::{Connection, RedisResult, ToRedisArgs};
+use redis::{from_redis_value, Connection, ErrorKind, RedisResult, ToRedisArgs, Value};
 
 use redis_streams::{
-    StreamClaimOptions, StreamClaimReply, StreamCommands, StreamInfoConsumersReply,
-    StreamInfoGroupsReply, StreamInfoStreamReply, StreamMaxlen, StreamPendingCountReply,
-    StreamPendingReply, StreamRangeReply, StreamReadOptions, StreamReadReply,
+    StreamAddAckOptions, StreamAddOptions, StreamAutoClaimOptions, StreamAutoClaimReply,
+    StreamBytesRangeReply, StreamBytesReadReply, StreamClaimOptions, StreamClaimReply,
+    StreamCommands, StreamEntryId, StreamErrorExt, StreamErrorKind, StreamGroupCreateOptions,
+    StreamIdSpec, StreamInfoConsumersReply, StreamInfoGroupsReply, StreamInfoStreamFullReply,
+    StreamInfoStreamReply, StreamMaxlen, StreamPendingCountReply, StreamPendingOptions,
+    StreamPendingReply, StreamRangeReply, StreamReadOptions, StreamReadReply, StreamSetIdOptions,
+    StreamTrimOptions,
 };
 
 use std::collections::BTreeMap;
 use std::str;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 
 use crate::support::*;
 
@@ -76,11 +80,55 @@ fn test_cmd_options() {
         "JUSTID"
     );
 
+    let empty = StreamAutoClaimOptions::default();
+    assert_eq!(ToRedisArgs::to_redis_args(&empty).len(), 0);
+
+    let opts = StreamAutoClaimOptions::default().count(25).with_justid();
+    assert_args!(&opts, "COUNT", "25", "JUSTID");
+
     // test maxlen options
 
     assert_args!(StreamMaxlen::Aprrox(10), "MAXLEN", "~", "10");
     assert_args!(StreamMaxlen::Equals(10), "MAXLEN", "=", "10");
 
+    // test trim & add options
+
+    assert_args!(StreamTrimOptions::maxlen(10), "MAXLEN", "=", "10");
+    assert_args!(
+        StreamTrimOptions::maxlen(10).approx().limit(5),
+        "MAXLEN",
+        "~",
+        "10",
+        "LIMIT",
+        "5"
+    );
+    assert_args!(
+        StreamTrimOptions::minid(StreamEntryId::new(1000, 0)).approx(),
+        "MINID",
+        "~",
+        "1000-0"
+    );
+    assert_args!(
+        StreamTrimOptions::from(StreamMaxlen::Aprrox(10)),
+        "MAXLEN",
+        "~",
+        "10"
+    );
+
+    assert_args!(StreamAddOptions::default(), "*");
+
+    let opts = StreamAddOptions::default()
+        .nomkstream()
+        .trim(StreamTrimOptions::minid((1000, 0)).exact())
+        .id((2000, 1));
+    assert_args!(&opts, "NOMKSTREAM", "MINID", "=", "1000-0", "2000-1");
+
+    let opts = StreamAddOptions::default()
+        .trim(StreamMaxlen::Equals(3))
+        .id((2000, 1))
+        .auto_id();
+    assert_args!(&opts, "MAXLEN", "=", "3", "*");
+
     // test read options
 
     let opts = StreamReadOptions::default()
@@ -105,6 +153,85 @@ fn test_cmd_options() {
     let opts = StreamReadOptions::default().noack().block(100).count(200);
 
     assert_args!(&opts, "BLOCK", "100", "COUNT", "200");
+
+    // test pending options
+
+    assert_args!(StreamPendingOptions::default(), "-", "+", "100");
+
+    let opts = StreamPendingOptions::default()
+        .consumer("c1")
+        .count(10)
+        .range(StreamIdSpec::Exclusive(StreamEntryId::new(1000, 0)), "+")
+        .idle(5000);
+    assert_args!(&opts, "IDLE", "5000", "(1000-0", "+", "10", "c1");
+
+    // test group & setid options
+
+    let empty = StreamGroupCreateOptions::default();
+    assert_eq!(ToRedisArgs::to_redis_args(&empty).len(), 0);
+
+    let opts = StreamGroupCreateOptions::default()
+        .entries_read(5)
+        .mkstream();
+    assert_args!(&opts, "MKSTREAM", "ENTRIESREAD", "5");
+
+    let opts = StreamSetIdOptions::default()
+        .max_deleted_id((1000, 1))
+        .entries_added(10);
+    assert_args!(&opts, "ENTRIESADDED", "10", "MAXDELETEDID", "1000-1");
+}
+
+#[test]
+fn test_stream_entry_id() {
+    // Tests the following....
+    // StreamEntryId parsing, ordering & stepping
+    // StreamIdSpec markers
+
+    let id: StreamEntryId = "1000-1".parse().unwrap();
+    assert_eq!(id, StreamEntryId::new(1000, 1));
+    assert_eq!(id.to_string(), "1000-1");
+    assert_args!(id, "1000-1");
+
+    // a bare ms is the first id for that ms
+    let id: StreamEntryId = "1000".parse().unwrap();
+    assert_eq!(id, StreamEntryId::new(1000, 0));
+
+    assert_eq!("1000-".parse::<StreamEntryId>().is_err(), true);
+    assert_eq!("abc-1".parse::<StreamEntryId>().is_err(), true);
+
+    let mut ids: Vec<StreamEntryId> = vec!["1001-0", "1000-10", "1000-2"]
+        .iter()
+        .map(|id| id.parse().unwrap())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["1000-2", "1000-10", "1001-0"]);
+
+    let id = StreamEntryId::new(1000, u64::MAX);
+    assert_eq!(id.next(), Some(StreamEntryId::new(1001, 0)));
+    assert_eq!(id.prev(), Some(StreamEntryId::new(1000, u64::MAX - 1)));
+    assert_eq!(StreamEntryId::new(1001, 0).prev(), Some(id));
+    assert_eq!(StreamEntryId::MIN.prev(), None);
+    assert_eq!(StreamEntryId::MAX.next(), None);
+
+    let time = UNIX_EPOCH + Duration::from_millis(1500);
+    let id = StreamEntryId::from_system_time(time);
+    assert_eq!(id, StreamEntryId::new(1500, 0));
+    assert_eq!(id.to_system_time(), time);
+
+    // test id markers
+
+    assert_args!(StreamIdSpec::Min, "-");
+    assert_args!(StreamIdSpec::Max, "+");
+    assert_args!(StreamIdSpec::Last, "$");
+    assert_args!(StreamIdSpec::Undelivered, ">");
+    assert_args!(StreamIdSpec::Auto, "*");
+    assert_args!(StreamIdSpec::Id(StreamEntryId::new(1, 2)), "1-2");
+    assert_args!(StreamIdSpec::Exclusive(StreamEntryId::new(1, 2)), "(1-2");
+
+    let spec: StreamIdSpec = "(1000-1".parse().unwrap();
+    assert_eq!(spec, StreamIdSpec::Exclusive(StreamEntryId::new(1000, 1)));
+    let spec: StreamIdSpec = ">".parse().unwrap();
+    assert_eq!(spec, StreamIdSpec::Undelivered);
 }
 
 #[test]
@@ -180,6 +307,7 @@ fn test_assorted_2() {
     // xinfo_stream
     // xinfo_groups
     // xinfo_consumer
+    // xinfo_stream_full
     // xgroup_create
     // xgroup_create_mkstream
     // xread_options
@@ -187,6 +315,7 @@ fn test_assorted_2() {
     // xpending
     // xpending_count
     // xpending_consumer_count
+    // xpending_options
 
     let ctx = TestContext::new();
     let mut con = ctx.connection();
@@ -262,6 +391,20 @@ fn test_assorted_2() {
     let reply: StreamInfoConsumersReply = con.xinfo_consumers("k99", "g99").unwrap();
     assert_eq!(reply.consumers[0].pending, 2);
 
+    // full stream info includes the group and consumer PELs
+    let reply: StreamInfoStreamFullReply = con.xinfo_stream_full("k99").unwrap();
+    assert_eq!(reply.length, 2);
+    assert_eq!(reply.entries.len(), 2);
+    assert_eq!(&reply.groups[0].name, "g99");
+    assert_eq!(reply.groups[0].pel_count, 2);
+    assert_eq!(reply.groups[0].pending[0].consumer, "c99");
+    assert_eq!(&reply.groups[0].consumers[0].name, "c99");
+    assert_eq!(reply.groups[0].consumers[0].pending.len(), 2);
+
+    let reply: StreamInfoStreamFullReply = con.xinfo_stream_full_count("k99", 1).unwrap();
+    assert_eq!(reply.entries.len(), 1);
+    assert_eq!(reply.groups[0].pending.len(), 1);
+
     // ack one of these messages
     let result: RedisResult<i32> = con.xack("k99", "g99", &["1000-0"]);
     assert_eq!(result, Ok(1));
@@ -315,6 +458,16 @@ fn test_assorted_2() {
         .xpending_consumer_count("k99", "g99", "-", "+", 10, "c99")
         .unwrap();
     assert_eq!(reply.ids.len(), 3);
+
+    // so does the options variation
+    let opts = StreamPendingOptions::default().consumer("c99").count(2);
+    let reply: StreamPendingCountReply = con.xpending_options("k99", "g99", opts).unwrap();
+    assert_eq!(reply.ids.len(), 2);
+
+    // nothing has been idle for an hour
+    let opts = StreamPendingOptions::default().idle(3_600_000);
+    let reply: StreamPendingCountReply = con.xpending_options("k99", "g99", opts).unwrap();
+    assert_eq!(reply.ids.len(), 0);
 }
 
 #[test]
@@ -382,9 +535,7 @@ fn test_xclaim() {
     sleep(Duration::from_millis(5));
 
     // grab this id if > 4ms
-    let reply: StreamClaimReply = con
-        .xclaim("k1", "g1", "c2", 4, &[claim.id.clone()])
-        .unwrap();
+    let reply: StreamClaimReply = con.xclaim("k1", "g1", "c2", 4, &[claim.id]).unwrap();
     assert_eq!(reply.ids.len(), 1);
     assert_eq!(reply.ids[0].id, claim.id);
 
@@ -409,7 +560,7 @@ fn test_xclaim() {
             "g1",
             "c3",
             4,
-            &[claim.id.clone()],
+            &[claim.id],
             StreamClaimOptions::default().with_force(),
         )
         .unwrap();
@@ -440,6 +591,86 @@ fn test_xclaim() {
     assert_eq!(claimed.len(), 10);
 }
 
+#[test]
+fn test_xautoclaim() {
+    // Tests the following commands....
+    // xautoclaim
+    // xautoclaim_options
+    // xautoclaim_iter
+    // (requires Redis 7 for the deleted ids behavior)
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let result: RedisResult<String> = con.xgroup_create_mkstream("k1", "g1", "$");
+    assert_eq!(result.is_ok(), true);
+
+    xadd_keyrange(&mut con, "k1", 0, 10);
+
+    // check out all 10 messages with c1
+    let reply: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+    assert_eq!(reply.keys[0].ids.len(), 10);
+    let ids = reply.keys[0].just_ids();
+
+    // nothing has been idle for 1s yet
+    let reply: StreamAutoClaimReply = con.xautoclaim("k1", "g1", "c2", 1000, "0-0").unwrap();
+    assert_eq!(reply.claimed.len(), 0);
+    assert_eq!(reply.next_stream_id, "0-0");
+
+    sleep(Duration::from_millis(5));
+
+    // claim the first 4 and check the cursor points at the 5th
+    let reply: StreamAutoClaimReply = con
+        .xautoclaim_options(
+            "k1",
+            "g1",
+            "c2",
+            4,
+            "0-0",
+            StreamAutoClaimOptions::default().count(4),
+        )
+        .unwrap();
+    assert_eq!(reply.claimed.len(), 4);
+    assert_eq!(&reply.claimed[0].id, ids[0]);
+    assert_eq!(reply.claimed[0].contains_key(&"h"), true);
+    assert_eq!(&reply.next_stream_id, ids[4]);
+
+    // delete a pending message so it's cleaned up by the next claim
+    let _: RedisResult<i32> = con.xdel("k1", &[ids[9]]);
+
+    sleep(Duration::from_millis(5));
+
+    // walk the rest of the pending entries list 2 at a time w/ JUSTID
+    let pages: Vec<StreamAutoClaimReply> = con
+        .xautoclaim_iter(
+            "k1",
+            "g1",
+            "c3",
+            4,
+            StreamAutoClaimOptions::default().count(2).with_justid(),
+        )
+        .collect::<RedisResult<_>>()
+        .unwrap();
+    assert_eq!(pages.len(), 5);
+    let claimed: usize = pages.iter().map(|page| page.claimed.len()).sum();
+    assert_eq!(claimed, 9);
+    assert_eq!(pages[0].claimed[0].len(), 0);
+    assert_eq!(pages.last().unwrap().next_stream_id, "0-0");
+
+    // everything left is checked out by c3
+    let reply: StreamPendingReply = con.xpending("k1", "g1").unwrap();
+    if let StreamPendingReply::Data(data) = reply {
+        assert_eq!(data.consumers.len(), 1);
+        assert_eq!(data.consumers[0].name, "c3");
+        assert_eq!(data.consumers[0].pending, 9);
+    }
+}
+
 #[test]
 fn test_xdel() {
     // Tests the following commands....
@@ -479,12 +710,71 @@ fn test_xtrim() {
     assert_eq!(result, Ok(40));
 }
 
+#[test]
+fn test_xadd_options() {
+    // Tests the following commands....
+    // xadd_options
+    // xadd_options_map
+    // xtrim_options
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    // NOMKSTREAM returns nil and doesn't create the key
+    let result: RedisResult<Option<String>> = con.xadd_options(
+        "k1",
+        &[("h", "w")],
+        StreamAddOptions::default().nomkstream(),
+    );
+    assert_eq!(result, Ok(None));
+    let result: RedisResult<usize> = con.xlen("k1");
+    assert_eq!(result, Ok(0));
+
+    // explicit ids
+    for i in 1..=10 {
+        let result: RedisResult<String> = con.xadd_options(
+            "k1",
+            &[("h", "w")],
+            StreamAddOptions::default().id((i * 1000, 0)),
+        );
+        assert_eq!(result, Ok(format!("{}-0", i * 1000)));
+    }
+
+    // add with MINID trims everything before 5000-0
+    let mut map: BTreeMap<&str, &str> = BTreeMap::new();
+    map.insert("h", "w");
+    let result: RedisResult<String> = con.xadd_options_map(
+        "k1",
+        map,
+        StreamAddOptions::default()
+            .nomkstream()
+            .trim(StreamTrimOptions::minid((5000, 0)))
+            .id((11000, 0)),
+    );
+    assert_eq!(result, Ok("11000-0".to_string()));
+    let reply: StreamRangeReply = con.xrange_all("k1").unwrap();
+    assert_eq!(reply.ids.len(), 7);
+    assert_eq!(reply.ids[0].id, "5000-0");
+
+    // xtrim w/ MAXLEN returns the number of evicted messages
+    let result: RedisResult<usize> = con.xtrim_options("k1", StreamTrimOptions::maxlen(5));
+    assert_eq!(result, Ok(2));
+
+    // xtrim w/ MINID
+    let result: RedisResult<usize> = con.xtrim_options("k1", StreamTrimOptions::minid((9000, 0)));
+    assert_eq!(result, Ok(2));
+    let result: RedisResult<usize> = con.xlen("k1");
+    assert_eq!(result, Ok(3));
+}
+
 #[test]
 fn test_xgroup() {
     // Tests the following commands....
     // xgroup_create_mkstream
     // xgroup_destroy
     // xgroup_delconsumer
+    // xgroup_create_options
+    // xgroup_createconsumer
+    // xsetid
 
     let ctx = TestContext::new();
     let mut con = ctx.connection();
@@ -520,6 +810,104 @@ fn test_xgroup() {
 
     let result: RedisResult<i32> = con.xgroup_destroy("k1", "g1");
     assert_eq!(result, Ok(1));
+
+    // create with options and register a consumer without reading
+    let result: RedisResult<String> = con.xgroup_create_options(
+        "k2",
+        "g2",
+        "0",
+        StreamGroupCreateOptions::default().mkstream(),
+    );
+    assert_eq!(result.is_ok(), true);
+
+    let result: RedisResult<i32> = con.xgroup_createconsumer("k2", "g2", "c2");
+    assert_eq!(result, Ok(1));
+    let result: RedisResult<i32> = con.xgroup_createconsumer("k2", "g2", "c2");
+    assert_eq!(result, Ok(0));
+
+    let reply: StreamInfoConsumersReply = con.xinfo_consumers("k2", "g2").unwrap();
+    assert_eq!(&reply.consumers[0].name, "c2");
+
+    // restore the last generated id
+    let result: RedisResult<String> = con.xsetid("k2", "5000-0");
+    assert_eq!(result.is_ok(), true);
+    let reply: StreamInfoStreamReply = con.xinfo_stream("k2").unwrap();
+    assert_eq!(&reply.last_generated_id, "5000-0");
+}
+
+#[test]
+fn test_stream_errors() {
+    // Tests the following....
+    // xgroup_create_if_missing
+    // xgroup_create_mkstream_if_missing
+    // stream_error_kind for BUSYGROUP, NOGROUP, small ids and invalid ids
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    // the key has to exist without MKSTREAM
+    let result = con.xgroup_create_if_missing("k1", "g1", "$");
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err().stream_error_kind(), None);
+
+    assert_eq!(
+        con.xgroup_create_mkstream_if_missing("k1", "g1", "$"),
+        Ok(true)
+    );
+    assert_eq!(
+        con.xgroup_create_mkstream_if_missing("k1", "g1", "$"),
+        Ok(false)
+    );
+    assert_eq!(con.xgroup_create_if_missing("k1", "g1", "$"), Ok(false));
+    assert_eq!(con.xgroup_create_if_missing("k1", "g2", "$"), Ok(true));
+
+    // BUSYGROUP
+    let err = con
+        .xgroup_create::<_, _, _, String>("k1", "g1", "$")
+        .unwrap_err();
+    assert_eq!(err.stream_error_kind(), Some(StreamErrorKind::BusyGroup));
+
+    // NOGROUP
+    let err = con
+        .xread_options::<_, _, StreamReadReply>(
+            &["k1"],
+            &[">"],
+            StreamReadOptions::default().group("g3", "c1"),
+        )
+        .unwrap_err();
+    assert_eq!(err.stream_error_kind(), Some(StreamErrorKind::NoGroup));
+
+    // XADD with an id equal to the top item or 0-0
+    let _: String = con.xadd("k1", "1000-0", &[("a", "b")]).unwrap();
+    let err = con
+        .xadd::<_, _, _, _, String>("k1", "1000-0", &[("a", "b")])
+        .unwrap_err();
+    assert_eq!(err.stream_error_kind(), Some(StreamErrorKind::IdTooSmall));
+    let err = con
+        .xadd::<_, _, _, _, String>("k2", "0-0", &[("a", "b")])
+        .unwrap_err();
+    assert_eq!(err.stream_error_kind(), Some(StreamErrorKind::IdTooSmall));
+
+    // XSETID below the top item
+    let err = con.xsetid::<_, _, String>("k1", "999-0").unwrap_err();
+    assert_eq!(err.stream_error_kind(), Some(StreamErrorKind::IdTooSmall));
+
+    // invalid ids
+    let err = con
+        .xadd::<_, _, _, _, String>("k1", "not-an-id", &[("a", "b")])
+        .unwrap_err();
+    assert_eq!(err.stream_error_kind(), Some(StreamErrorKind::InvalidId));
+
+    // other errors aren't stream errors
+    let _: () = redis::cmd("SET")
+        .arg("k3")
+        .arg("v")
+        .query(&mut con)
+        .unwrap();
+    let err = con
+        .xadd::<_, _, _, _, String>("k3", "*", &[("a", "b")])
+        .unwrap_err();
+    assert_eq!(err.stream_error_kind(), None);
 }
 
 #[test]
@@ -528,6 +916,8 @@ fn test_xrange() {
     // xrange (-/+ variations)
     // xrange_all
     // xrange_count
+    // xrange_iter
+    // xrange_page_iter
 
     let ctx = TestContext::new();
     let mut con = ctx.connection();
@@ -546,6 +936,51 @@ fn test_xrange() {
 
     let reply: StreamRangeReply = con.xrange_count("k1", "-", "+", 1).unwrap();
     assert_eq!(reply.ids.len(), 1);
+
+    // typed ids and markers work as range bounds
+    let reply: StreamRangeReply = con
+        .xrange("k1", StreamEntryId::new(1000, 1), StreamIdSpec::Max)
+        .unwrap();
+    assert_eq!(reply.ids.len(), 1);
+    assert_eq!(reply.ids[0].id, StreamEntryId::new(1000, 1));
+
+    // xrange_iter / xrange_page_iter
+    for seq in 0..5 {
+        let _: RedisResult<String> = con.xadd("k2", format!("2000-{}", seq), &[("seq", seq)]);
+    }
+    let _: RedisResult<String> = con.xadd("k2", "3000-0", &[("seq", 5)]);
+
+    let ids: Vec<String> = con
+        .xrange_iter("k2", StreamEntryId::MIN, StreamEntryId::MAX, 2)
+        .map(|msg| msg.unwrap().id.to_string())
+        .collect();
+    assert_eq!(
+        ids,
+        vec!["2000-0", "2000-1", "2000-2", "2000-3", "2000-4", "3000-0"]
+    );
+
+    // pages are split on the exclusive next id and stop at the end bound
+    let pages: Vec<usize> = con
+        .xrange_page_iter(
+            "k2",
+            "2000-1".parse().unwrap(),
+            "2000-4".parse().unwrap(),
+            2,
+        )
+        .map(|page| page.unwrap().ids.len())
+        .collect();
+    assert_eq!(pages, vec![2, 2]);
+
+    // time bounds
+    let end = StreamEntryId::from_system_time(UNIX_EPOCH + Duration::from_millis(2999));
+    let count = con.xrange_iter("k2", StreamEntryId::MIN, end, 100).count();
+    assert_eq!(count, 5);
+
+    // empty ranges yield nothing
+    let count = con
+        .xrange_page_iter("k3", StreamEntryId::MIN, StreamEntryId::MAX, 10)
+        .count();
+    assert_eq!(count, 0);
 }
 
 #[test]
@@ -554,6 +989,8 @@ fn test_xrevrange() {
     // xrevrange (+/- variations)
     // xrevrange_all
     // xrevrange_count
+    // xrevrange_iter
+    // xrevrange_page_iter
 
     let ctx = TestContext::new();
     let mut con = ctx.connection();
@@ -572,4 +1009,224 @@ fn test_xrevrange() {
 
     let reply: StreamRangeReply = con.xrevrange_count("k1", "+", "-", 1).unwrap();
     assert_eq!(reply.ids.len(), 1);
+
+    // xrevrange_iter / xrevrange_page_iter
+    for seq in 0..5 {
+        let _: RedisResult<String> = con.xadd("k2", format!("2000-{}", seq), &[("seq", seq)]);
+    }
+
+    let ids: Vec<String> = con
+        .xrevrange_iter("k2", StreamEntryId::MAX, StreamEntryId::MIN, 2)
+        .map(|msg| msg.unwrap().id.to_string())
+        .collect();
+    assert_eq!(ids, vec!["2000-4", "2000-3", "2000-2", "2000-1", "2000-0"]);
+
+    let pages: Vec<usize> = con
+        .xrevrange_page_iter(
+            "k2",
+            "2000-3".parse().unwrap(),
+            "2000-1".parse().unwrap(),
+            2,
+        )
+        .map(|page| page.unwrap().ids.len())
+        .collect();
+    assert_eq!(pages, vec![2, 1]);
+}
+
+#[test]
+fn test_bytes_replies() {
+    // Tests the following....
+    // non-utf8 keys and field names survive xread/xrange
+    // utf8 replies fail on the same data
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let key: &[u8] = b"k\xff1";
+    let field: &[u8] = b"f\xfe";
+    let value: &[u8] = b"\x00\x01\x02";
+
+    let result: RedisResult<String> = con.xadd(key, "1000-0", &[(field, value)]);
+    assert_eq!(result, Ok("1000-0".to_string()));
+
+    let reply: StreamBytesReadReply = con.xread(&[key], &["0"]).unwrap();
+    assert_eq!(reply.keys.len(), 1);
+    assert_eq!(reply.keys[0].key, key.to_vec());
+    assert_eq!(reply.keys[0].just_ids(), vec![&StreamEntryId::new(1000, 0)]);
+    let id = &reply.keys[0].ids[0];
+    assert_eq!(id.contains_key(field), true);
+    assert_eq!(id.get::<Vec<u8>>(field), Some(value.to_vec()));
+
+    let reply: StreamBytesRangeReply = con.xrange_all(key).unwrap();
+    assert_eq!(reply.ids.len(), 1);
+    assert_eq!(reply.ids[0].len(), 1);
+
+    let reply: StreamBytesRangeReply = con.xrevrange_count(key, "+", "-", 1).unwrap();
+    assert_eq!(reply.ids[0].id, "1000-0");
+
+    let result: RedisResult<StreamRangeReply> = con.xrange_all(key);
+    assert_eq!(result.is_err(), true);
+
+    // blocking reads time out with nil
+    let reply: StreamBytesReadReply = con
+        .xread_options(&[key], &["$"], StreamReadOptions::default().block(1))
+        .unwrap();
+    assert_eq!(reply.keys.len(), 0);
+}
+
+#[test]
+fn test_stream_id_fields() {
+    // Tests the following....
+    // field order and duplicate fields are kept
+    // StreamId writes its field/values back out in order
+    // Nil values fail to encode
+
+    let entry = Value::Bulk(vec![
+        Value::Data(b"1000-0".to_vec()),
+        Value::Bulk(vec![
+            Value::Data(b"b".to_vec()),
+            Value::Data(b"1".to_vec()),
+            Value::Data(b"a".to_vec()),
+            Value::Data(b"2".to_vec()),
+            Value::Data(b"b".to_vec()),
+            Value::Data(b"3".to_vec()),
+        ]),
+    ]);
+    let reply: StreamRangeReply = from_redis_value(&Value::Bulk(vec![entry])).unwrap();
+    let id = &reply.ids[0];
+
+    let fields: Vec<&str> = id.fields.iter().map(|(f, _)| f.as_str()).collect();
+    assert_eq!(fields, vec!["b", "a", "b"]);
+    assert_eq!(id.len(), 3);
+    assert_eq!(id.get("b"), Some(1));
+    assert_eq!(id.find_all("b").len(), 2);
+    assert_eq!(id.to_map().len(), 2);
+    assert_args!(id.to_stream_fields().unwrap(), "b", "1", "a", "2", "b", "3");
+
+    // values with no argument form are an error, not an empty string
+    let mut nil = id.clone();
+    nil.fields.push(("c".to_string(), Value::Nil));
+    let err = nil.to_stream_fields().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeError);
+}
+
+#[test]
+fn test_xrange_roundtrip() {
+    // Tests the following....
+    // an entry read with xrange can be added back with xadd_map
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xadd("k1", "1000-0", &[("z", "1"), ("a", "2"), ("z", "3")]);
+
+    let reply: StreamRangeReply = con.xrange_all("k1").unwrap();
+    let entry = &reply.ids[0];
+    assert_eq!(entry.len(), 3);
+
+    let result: RedisResult<String> =
+        con.xadd_map("k2", entry.id, entry.to_stream_fields().unwrap());
+    assert_eq!(result, Ok("1000-0".to_string()));
+
+    let copy: StreamRangeReply = con.xrange_all("k2").unwrap();
+    assert_eq!(copy.ids[0], *entry);
+}
+
+#[test]
+fn test_xadd_xack() {
+    // Tests the following commands....
+    // xadd_xack
+
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let _: RedisResult<String> = con.xgroup_create_mkstream("in", "g1", "0");
+    let _: RedisResult<String> = con.xadd("in", "1000-0", &[("n", "1")]);
+    let _: RedisResult<String> = con.xadd("in", "1000-1", &[("n", "2")]);
+    let _: StreamReadReply = con
+        .xread_options(
+            &["in"],
+            &[">"],
+            StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+
+    // produce to "out" and ack "in" together
+    let id = con
+        .xadd_xack(
+            "out",
+            &[("total", "1")],
+            "in",
+            "g1",
+            "1000-0",
+            StreamAddAckOptions::default().add_options(StreamAddOptions::default().id((2000, 0))),
+        )
+        .unwrap();
+    assert_eq!(id, Some("2000-0".to_string()));
+
+    let reply: StreamPendingCountReply = con.xpending_count("in", "g1", "-", "+", 10).unwrap();
+    assert_eq!(reply.ids.len(), 1);
+    assert_eq!(reply.ids[0].id, "1000-1");
+
+    // already acked, so nothing is produced
+    let id = con
+        .xadd_xack(
+            "out",
+            &[("total", "1")],
+            "in",
+            "g1",
+            "1000-0",
+            StreamAddAckOptions::default(),
+        )
+        .unwrap();
+    assert_eq!(id, None);
+    let len: usize = con.xlen("out").unwrap();
+    assert_eq!(len, 1);
+
+    // a failing xadd leaves the source message pending
+    let result = con.xadd_xack(
+        "out",
+        &[("total", "2")],
+        "in",
+        "g1",
+        "1000-1",
+        StreamAddAckOptions::default().add_options(StreamAddOptions::default().id((1, 0))),
+    );
+    assert!(result.is_err());
+    let reply: StreamPendingCountReply = con.xpending_count("in", "g1", "-", "+", 10).unwrap();
+    assert_eq!(reply.ids.len(), 1);
+    let len: usize = con.xlen("out").unwrap();
+    assert_eq!(len, 1);
+
+    // so does a missing destination with NOMKSTREAM
+    let result = con.xadd_xack(
+        "missing",
+        &[("total", "2")],
+        "in",
+        "g1",
+        "1000-1",
+        StreamAddAckOptions::default().add_options(StreamAddOptions::default().nomkstream()),
+    );
+    assert!(result.is_err());
+    let reply: StreamPendingCountReply = con.xpending_count("in", "g1", "-", "+", 10).unwrap();
+    assert_eq!(reply.ids.len(), 1);
+
+    // ack and delete from the source
+    let id = con
+        .xadd_xack(
+            "out",
+            &[("total", "2")],
+            "in",
+            "g1",
+            "1000-1",
+            StreamAddAckOptions::default().with_delete(),
+        )
+        .unwrap();
+    assert!(id.is_some());
+    let reply: StreamPendingCountReply = con.xpending_count("in", "g1", "-", "+", 10).unwrap();
+    assert_eq!(reply.ids.len(), 0);
+    let len: usize = con.xlen("in").unwrap();
+    assert_eq!(len, 1);
+    let len: usize = con.xlen("out").unwrap();
+    assert_eq!(len, 2);
 }